@@ -208,5 +208,10 @@ fn development_genesis(
 		treasury: Default::default(),
 		aura_ext: Default::default(),
 		parachain_system: Default::default(),
+		imbue_proposals: development_runtime::ImbueProposalsConfig {
+			init_max_proposal_count_per_round: development_runtime::MaxProposalsPerRound::get(),
+			init_withdrawal_expiration: development_runtime::MaxWithdrawalExpiration::get(),
+			init_is_identity_required: false,
+		},
 	}
 }
@@ -0,0 +1,43 @@
+//! Runtime API definition for the proposals pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::prelude::*;
+
+pub use proposals::{ContributorFunds, MilestoneIndex, MilestonePayout, Project, ProjectContent, ProjectIndex, ProjectState, Round, RoundIndex, Vote};
+
+sp_api::decl_runtime_apis! {
+	pub trait ProposalsApi<AccountId, Balance, BlockNumber> where
+		AccountId: Codec,
+		Balance: Codec,
+		BlockNumber: Codec,
+	{
+		/// Get all projects
+		fn get_projects() -> Vec<Project<AccountId, Balance, BlockNumber>>;
+
+		/// Get a single project, `None` if the key is unknown
+		fn get_project(project_key: ProjectIndex) -> Option<Project<AccountId, Balance, BlockNumber>>;
+
+		/// Get the keys of the projects in a state
+		fn get_projects_by_state(state: ProjectState) -> Vec<ProjectIndex>;
+
+		/// Get the round that is currently accepting contributions and votes
+		fn get_active_round() -> Option<(RoundIndex, Round<Balance, BlockNumber>)>;
+
+		/// Get the projects taking part in a round
+		fn get_round_projects(round_index: RoundIndex) -> Vec<ProjectIndex>;
+
+		/// Get the yay/nay tally of every milestone of a project
+		fn get_milestone_votes(project_key: ProjectIndex) -> Vec<(MilestoneIndex, Vote<Balance>)>;
+
+		/// Get how much of a contributor's funds have been unlocked and are withdrawable
+		fn get_contributor_funds(project_key: ProjectIndex, who: AccountId) -> ContributorFunds<Balance>;
+
+		/// Get what the owner has been paid for each milestone of a project
+		fn get_milestone_payouts(project_key: ProjectIndex) -> Vec<(MilestoneIndex, MilestonePayout<Balance, BlockNumber>)>;
+
+		/// Get the funds of a project that no approved milestone has unlocked yet
+		fn get_locked_funds(project_key: ProjectIndex) -> Balance;
+	}
+}
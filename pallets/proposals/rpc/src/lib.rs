@@ -0,0 +1,140 @@
+//! RPC interface for the proposals pallet.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use proposals_rpc_runtime_api::ProposalsApi as ProposalsRuntimeApi;
+use proposals_rpc_runtime_api::{ContributorFunds, MilestoneIndex, MilestonePayout, Project, ProjectIndex, ProjectState, Round, RoundIndex, Vote};
+
+/// Error code returned when the runtime API call fails.
+const RUNTIME_ERROR: i64 = 1;
+
+#[rpc]
+pub trait ProposalsApi<BlockHash, AccountId, Balance, BlockNumber> {
+	#[rpc(name = "proposals_getProjects")]
+	fn get_projects(&self, at: Option<BlockHash>) -> Result<Vec<Project<AccountId, Balance, BlockNumber>>>;
+
+	#[rpc(name = "proposals_getProject")]
+	fn get_project(&self, project_key: ProjectIndex, at: Option<BlockHash>) -> Result<Option<Project<AccountId, Balance, BlockNumber>>>;
+
+	#[rpc(name = "proposals_getProjectsByState")]
+	fn get_projects_by_state(&self, state: ProjectState, at: Option<BlockHash>) -> Result<Vec<ProjectIndex>>;
+
+	#[rpc(name = "proposals_getActiveRound")]
+	fn get_active_round(&self, at: Option<BlockHash>) -> Result<Option<(RoundIndex, Round<Balance, BlockNumber>)>>;
+
+	#[rpc(name = "proposals_getRoundProjects")]
+	fn get_round_projects(&self, round_index: RoundIndex, at: Option<BlockHash>) -> Result<Vec<ProjectIndex>>;
+
+	#[rpc(name = "proposals_getMilestoneVotes")]
+	fn get_milestone_votes(&self, project_key: ProjectIndex, at: Option<BlockHash>) -> Result<Vec<(MilestoneIndex, Vote<Balance>)>>;
+
+	#[rpc(name = "proposals_getContributorFunds")]
+	fn get_contributor_funds(&self, project_key: ProjectIndex, who: AccountId, at: Option<BlockHash>) -> Result<ContributorFunds<Balance>>;
+
+	#[rpc(name = "proposals_getMilestonePayouts")]
+	fn get_milestone_payouts(&self, project_key: ProjectIndex, at: Option<BlockHash>) -> Result<Vec<(MilestoneIndex, MilestonePayout<Balance, BlockNumber>)>>;
+
+	#[rpc(name = "proposals_getLockedFunds")]
+	fn get_locked_funds(&self, project_key: ProjectIndex, at: Option<BlockHash>) -> Result<Balance>;
+}
+
+/// Implements the `ProposalsApi` RPC trait by calling into the runtime.
+pub struct Proposals<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Proposals<C, B> {
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+fn runtime_error(message: &str, e: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(RUNTIME_ERROR),
+		message: message.into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+impl<C, Block, AccountId, Balance, BlockNumber> ProposalsApi<<Block as BlockT>::Hash, AccountId, Balance, BlockNumber>
+	for Proposals<C, Block>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: ProposalsRuntimeApi<Block, AccountId, Balance, BlockNumber>,
+	AccountId: Codec,
+	Balance: Codec,
+	BlockNumber: Codec,
+{
+	fn get_projects(&self, at: Option<<Block as BlockT>::Hash>) -> Result<Vec<Project<AccountId, Balance, BlockNumber>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.get_projects(&at).map_err(|e| runtime_error("Unable to query projects.", e))
+	}
+
+	fn get_project(&self, project_key: ProjectIndex, at: Option<<Block as BlockT>::Hash>) -> Result<Option<Project<AccountId, Balance, BlockNumber>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.get_project(&at, project_key).map_err(|e| runtime_error("Unable to query project.", e))
+	}
+
+	fn get_projects_by_state(&self, state: ProjectState, at: Option<<Block as BlockT>::Hash>) -> Result<Vec<ProjectIndex>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.get_projects_by_state(&at, state).map_err(|e| runtime_error("Unable to query projects by state.", e))
+	}
+
+	fn get_active_round(&self, at: Option<<Block as BlockT>::Hash>) -> Result<Option<(RoundIndex, Round<Balance, BlockNumber>)>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.get_active_round(&at).map_err(|e| runtime_error("Unable to query active round.", e))
+	}
+
+	fn get_round_projects(&self, round_index: RoundIndex, at: Option<<Block as BlockT>::Hash>) -> Result<Vec<ProjectIndex>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.get_round_projects(&at, round_index).map_err(|e| runtime_error("Unable to query round projects.", e))
+	}
+
+	fn get_milestone_votes(&self, project_key: ProjectIndex, at: Option<<Block as BlockT>::Hash>) -> Result<Vec<(MilestoneIndex, Vote<Balance>)>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.get_milestone_votes(&at, project_key).map_err(|e| runtime_error("Unable to query milestone votes.", e))
+	}
+
+	fn get_contributor_funds(&self, project_key: ProjectIndex, who: AccountId, at: Option<<Block as BlockT>::Hash>) -> Result<ContributorFunds<Balance>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.get_contributor_funds(&at, project_key, who).map_err(|e| runtime_error("Unable to query contributor funds.", e))
+	}
+
+	fn get_milestone_payouts(&self, project_key: ProjectIndex, at: Option<<Block as BlockT>::Hash>) -> Result<Vec<(MilestoneIndex, MilestonePayout<Balance, BlockNumber>)>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.get_milestone_payouts(&at, project_key).map_err(|e| runtime_error("Unable to query milestone payouts.", e))
+	}
+
+	fn get_locked_funds(&self, project_key: ProjectIndex, at: Option<<Block as BlockT>::Hash>) -> Result<Balance> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.get_locked_funds(&at, project_key).map_err(|e| runtime_error("Unable to query locked funds.", e))
+	}
+}
@@ -0,0 +1,487 @@
+//! Benchmarking setup for the proposals pallet
+
+use super::*;
+
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use frame_support::{assert_ok, traits::Currency};
+use frame_system::{Pallet as System, RawOrigin};
+use sp_runtime::traits::Bounded;
+
+const SEED: u32 = 0;
+const MAX_CONTRIBUTORS: u32 = 1_000;
+
+fn assert_last_event<T: Config>(generic_event: <T as Config>::Event) {
+	frame_system::Pallet::<T>::assert_last_event(generic_event.into());
+}
+
+fn funded_account<T: Config>(name: &'static str, index: u32) -> T::AccountId {
+	let who: T::AccountId = account(name, index, SEED);
+	<T as Config>::Currency::make_free_balance_be(&who, BalanceOf::<T>::max_value() / 1_000_000u32.into());
+	who
+}
+
+fn contribution_value<T: Config>() -> BalanceOf<T> {
+	<T as Config>::Currency::minimum_balance().saturating_mul(100u32.into()).max(1_000_000u32.into())
+}
+
+/// A hard cap that every contributor of a benchmark fits under
+fn hard_cap<T: Config>() -> BalanceOf<T> {
+	contribution_value::<T>().saturating_mul(MAX_CONTRIBUTORS.into())
+}
+
+/// Split 100% over `milestones`, giving the first milestone what does not divide evenly
+fn milestone_percentages(milestones: u32) -> impl Iterator<Item = u32> {
+	(0..milestones).map(move |milestone_index| if milestone_index == 0 { 100 / milestones + 100 % milestones } else { 100 / milestones })
+}
+
+fn create_project<T: Config>(owner: &T::AccountId, milestones: u32) -> ProjectIndex {
+	IsIdentityRequired::<T>::put(false);
+	let project_key = ProjectCount::<T>::get();
+	let field = vec![0u8; T::MaxStringLength::get() as usize];
+	let content = ProjectContent::Raw(vec![0u8; T::MaxContentLength::get() as usize]);
+	let proposed_milestones = milestone_percentages(milestones)
+		.map(|percentage_to_unlock| ProposedMilestone {
+			name: field.clone(),
+			percentage_to_unlock,
+		})
+		.collect();
+
+	assert_ok!(Pallet::<T>::create_project(
+		RawOrigin::Signed(owner.clone()).into(),
+		field.clone(),
+		content.clone(),
+		content,
+		field,
+		proposed_milestones,
+		hard_cap::<T>(),
+		contribution_value::<T>(),
+	));
+	project_key
+}
+
+/// Schedule a round from block 2 to block 10 with every milestone of the given projects
+fn schedule_round<T: Config>(project_keys: Vec<ProjectIndex>, milestones: u32) -> RoundIndex {
+	let round_index = RoundCount::<T>::get();
+	MaxProposalCountPerRound::<T>::put(T::MaxProposalsPerRound::get());
+	let projects = project_keys.into_iter()
+		.map(|project_key| (project_key, (0..milestones).collect()))
+		.collect();
+
+	System::<T>::set_block_number(1u32.into());
+	assert_ok!(Pallet::<T>::schedule_round(T::ScheduleOrigin::successful_origin(), 2u32.into(), 10u32.into(), projects));
+	round_index
+}
+
+/// Create a project in an open round, returning its owner and key
+fn open_project<T: Config>(milestones: u32) -> (T::AccountId, ProjectIndex, RoundIndex) {
+	let owner = funded_account::<T>("owner", 0);
+	let project_key = create_project::<T>(&owner, milestones);
+	let round_index = schedule_round::<T>(vec![project_key], milestones);
+	System::<T>::set_block_number(2u32.into());
+	(owner, project_key, round_index)
+}
+
+/// Schedule `count` consecutive rounds and move to the start of the last one, which proposes every milestone of a new project
+/// The earlier rounds have ended without being finalised, so finding the processing round passes over all of them.
+fn open_project_after_rounds<T: Config>(milestones: u32, count: u32) -> (T::AccountId, ProjectIndex, RoundIndex) {
+	let owner = funded_account::<T>("owner", 0);
+	MaxProposalCountPerRound::<T>::put(T::MaxProposalsPerRound::get());
+	System::<T>::set_block_number(1u32.into());
+	let mut project_key = 0;
+	let mut start = 2u32;
+	for round in 0..count {
+		project_key = create_project::<T>(&owner, milestones);
+		start = 2 + 10 * round;
+		let projects = vec![(project_key, (0..milestones).collect())];
+		assert_ok!(Pallet::<T>::schedule_round(T::ScheduleOrigin::successful_origin(), start.into(), (start + 8).into(), projects));
+	}
+	System::<T>::set_block_number(start.into());
+	(owner, project_key, RoundCount::<T>::get() - 1)
+}
+
+fn contribute<T: Config>(who: &T::AccountId, project_key: ProjectIndex) {
+	assert_ok!(Pallet::<T>::contribute(RawOrigin::Signed(who.clone()).into(), project_key, contribution_value::<T>()));
+}
+
+fn submit_milestone<T: Config>(owner: &T::AccountId, project_key: ProjectIndex, milestone_index: MilestoneIndex) {
+	assert_ok!(Pallet::<T>::submit_milestone(RawOrigin::Signed(owner.clone()).into(), project_key, milestone_index, vec![0u8; T::MaxStringLength::get() as usize]));
+}
+
+/// Approve every milestone of a project in an ended round
+fn approve_milestones<T: Config>(owner: &T::AccountId, project_key: ProjectIndex, round_index: RoundIndex, milestones: u32) {
+	let contributor = funded_account::<T>("contributor", 0);
+	contribute::<T>(&contributor, project_key);
+	for milestone_index in 0..milestones {
+		submit_milestone::<T>(owner, project_key, milestone_index);
+		assert_ok!(Pallet::<T>::vote_on_milestone(RawOrigin::Signed(contributor.clone()).into(), project_key, milestone_index, true));
+	}
+	System::<T>::set_block_number(11u32.into());
+	assert_ok!(Pallet::<T>::approve(T::ApproveOrigin::successful_origin(), round_index, project_key, (0..milestones).collect()));
+}
+
+/// Create a single milestone project whose milestone was rejected by a finalised round
+fn rejected_milestone<T: Config>() -> (T::AccountId, ProjectIndex, RoundIndex) {
+	let (owner, project_key, round_index) = open_project::<T>(1);
+	let contributor = funded_account::<T>("contributor", 0);
+	contribute::<T>(&contributor, project_key);
+	submit_milestone::<T>(&owner, project_key, 0);
+	System::<T>::set_block_number(11u32.into());
+	Pallet::<T>::finalize_round(round_index, 11u32.into());
+	(owner, project_key, round_index)
+}
+
+benchmarks! {
+	create_project {
+		let m in 1 .. T::MaxMilestonesPerProject::get();
+		IsIdentityRequired::<T>::put(false);
+		let caller: T::AccountId = whitelisted_caller();
+		<T as Config>::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value() / 1_000_000u32.into());
+		let field = vec![0u8; T::MaxStringLength::get() as usize];
+		let content = ProjectContent::Raw(vec![0u8; T::MaxContentLength::get() as usize]);
+		let proposed_milestones = milestone_percentages(m)
+			.map(|percentage_to_unlock| ProposedMilestone {
+				name: field.clone(),
+				percentage_to_unlock,
+			})
+			.collect();
+	}: _(RawOrigin::Signed(caller), field.clone(), content.clone(), content, field, proposed_milestones, hard_cap::<T>(), contribution_value::<T>())
+	verify {
+		assert_last_event::<T>(Event::ProjectCreated(0).into());
+	}
+
+	update_project {
+		let caller = funded_account::<T>("owner", 0);
+		let project_key = create_project::<T>(&caller, 1);
+		let field = vec![1u8; T::MaxStringLength::get() as usize];
+		let content = ProjectContent::Raw(vec![1u8; T::MaxContentLength::get() as usize]);
+	}: _(RawOrigin::Signed(caller), project_key, field.clone(), content.clone(), content, field)
+	verify {
+		assert_last_event::<T>(Event::ProjectUpdated(project_key).into());
+	}
+
+	update_milestones {
+		let m in 1 .. T::MaxMilestonesPerProject::get();
+		let caller = funded_account::<T>("owner", 0);
+		let project_key = create_project::<T>(&caller, 1);
+		let field = vec![1u8; T::MaxStringLength::get() as usize];
+		let proposed_milestones = milestone_percentages(m)
+			.map(|percentage_to_unlock| ProposedMilestone {
+				name: field.clone(),
+				percentage_to_unlock,
+			})
+			.collect();
+	}: _(RawOrigin::Signed(caller), project_key, proposed_milestones)
+	verify {
+		assert_last_event::<T>(Event::MilestonesUpdated(project_key).into());
+	}
+
+	set_funding_currency {
+		let caller = funded_account::<T>("owner", 0);
+		let project_key = create_project::<T>(&caller, 1);
+	}: _(RawOrigin::Signed(caller), project_key, FundingCurrency::Native)
+	verify {
+		assert_last_event::<T>(Event::FundingCurrencySet(project_key, FundingCurrency::Native).into());
+	}
+
+	set_vote_weighting {
+		let caller = funded_account::<T>("owner", 0);
+		let project_key = create_project::<T>(&caller, 1);
+	}: _(RawOrigin::Signed(caller), project_key, VoteWeighting::SquareRoot)
+	verify {
+		assert_last_event::<T>(Event::VoteWeightingSet(project_key, VoteWeighting::SquareRoot).into());
+	}
+
+	propose_owner {
+		let caller = funded_account::<T>("owner", 0);
+		let project_key = create_project::<T>(&caller, 1);
+		let new_owner = funded_account::<T>("new_owner", 0);
+	}: _(RawOrigin::Signed(caller), project_key, Some(new_owner.clone()))
+	verify {
+		assert_last_event::<T>(Event::OwnerProposed(project_key, Some(new_owner)).into());
+	}
+
+	accept_ownership {
+		let owner = funded_account::<T>("owner", 0);
+		let project_key = create_project::<T>(&owner, 1);
+		let caller = funded_account::<T>("new_owner", 0);
+		assert_ok!(Pallet::<T>::propose_owner(RawOrigin::Signed(owner.clone()).into(), project_key, Some(caller.clone())));
+	}: _(RawOrigin::Signed(caller.clone()), project_key)
+	verify {
+		assert_last_event::<T>(Event::OwnershipTransferred(project_key, owner, caller).into());
+	}
+
+	schedule_round {
+		let s in 1 .. T::MaxProposalsPerRound::get();
+		let owner = funded_account::<T>("owner", 0);
+		let projects: Vec<(ProjectIndex, Vec<MilestoneIndex>)> = (0..s)
+			.map(|_| (create_project::<T>(&owner, 1), vec![0]))
+			.collect();
+		MaxProposalCountPerRound::<T>::put(T::MaxProposalsPerRound::get());
+		System::<T>::set_block_number(1u32.into());
+		let origin = T::ScheduleOrigin::successful_origin();
+	}: _<T::Origin>(origin, 2u32.into(), 10u32.into(), projects)
+	verify {
+		assert_last_event::<T>(Event::RoundCreated(0).into());
+	}
+
+	cancel_round {
+		let owner = funded_account::<T>("owner", 0);
+		let project_key = create_project::<T>(&owner, 1);
+		let round_index = schedule_round::<T>(vec![project_key], 1);
+		System::<T>::set_block_number(0u32.into());
+		let origin = T::ScheduleOrigin::successful_origin();
+	}: _<T::Origin>(origin, round_index)
+
+	start_round {
+		let (_, _, round_index) = open_project::<T>(1);
+	}: _(RawOrigin::Root, round_index)
+	verify {
+		assert_last_event::<T>(Event::RoundStarted(round_index).into());
+	}
+
+	end_round {
+		let (_, _, round_index) = open_project::<T>(1);
+		System::<T>::set_block_number(10u32.into());
+	}: _(RawOrigin::Root, round_index)
+	verify {
+		assert_last_event::<T>(Event::RoundEnded(round_index).into());
+	}
+
+	fund {
+		let (_, _, round_index) = open_project::<T>(1);
+		let caller = funded_account::<T>("funder", 0);
+		let value = contribution_value::<T>();
+	}: _(RawOrigin::Signed(caller.clone()), round_index, value)
+	verify {
+		assert_last_event::<T>(Event::FundSucceed(caller, round_index, value).into());
+	}
+
+	submit_milestone {
+		let (owner, project_key, _) = open_project::<T>(T::MaxMilestonesPerProject::get());
+		let milestone_index = T::MaxMilestonesPerProject::get() - 1;
+		let contributor = funded_account::<T>("contributor", 0);
+		contribute::<T>(&contributor, project_key);
+	}: _(RawOrigin::Signed(owner), project_key, milestone_index, vec![0u8; T::MaxStringLength::get() as usize])
+	verify {
+		assert!(MilestoneSubmissions::<T>::contains_key((project_key, milestone_index)));
+	}
+
+	// The worst case replaces an earlier vote
+	vote_on_milestone {
+		let (owner, project_key, _) = open_project::<T>(1);
+		let caller = funded_account::<T>("contributor", 0);
+		contribute::<T>(&caller, project_key);
+		submit_milestone::<T>(&owner, project_key, 0);
+		assert_ok!(Pallet::<T>::vote_on_milestone(RawOrigin::Signed(caller.clone()).into(), project_key, 0, false));
+	}: _(RawOrigin::Signed(caller), project_key, 0, true)
+
+	abstain_on_milestone {
+		let (owner, project_key, _) = open_project::<T>(1);
+		let mut rules = Pallet::<T>::voting_rules(project_key);
+		rules.allow_abstain = true;
+		ProjectVotingRules::<T>::insert(project_key, rules);
+		let caller = funded_account::<T>("contributor", 0);
+		contribute::<T>(&caller, project_key);
+		submit_milestone::<T>(&owner, project_key, 0);
+		assert_ok!(Pallet::<T>::vote_on_milestone(RawOrigin::Signed(caller.clone()).into(), project_key, 0, true));
+	}: _(RawOrigin::Signed(caller), project_key, 0)
+
+	retract_vote {
+		let (owner, project_key, _) = open_project::<T>(1);
+		let caller = funded_account::<T>("contributor", 0);
+		contribute::<T>(&caller, project_key);
+		submit_milestone::<T>(&owner, project_key, 0);
+		assert_ok!(Pallet::<T>::vote_on_milestone(RawOrigin::Signed(caller.clone()).into(), project_key, 0, true));
+	}: _(RawOrigin::Signed(caller), project_key, 0)
+
+	// The worst case adds to an earlier contribution
+	contribute {
+		let u in 1 .. T::MaxUpcomingRounds::get();
+		let (_, project_key, _) = open_project_after_rounds::<T>(1, u);
+		let caller = funded_account::<T>("contributor", 0);
+		contribute::<T>(&caller, project_key);
+		let value = contribution_value::<T>();
+	}: _(RawOrigin::Signed(caller), project_key, value)
+
+	approve {
+		let m in 1 .. T::MaxMilestonesPerProject::get();
+		let (owner, project_key, round_index) = open_project::<T>(m);
+		let contributor = funded_account::<T>("contributor", 0);
+		contribute::<T>(&contributor, project_key);
+		for milestone_index in 0..m {
+			submit_milestone::<T>(&owner, project_key, milestone_index);
+			assert_ok!(Pallet::<T>::vote_on_milestone(RawOrigin::Signed(contributor.clone()).into(), project_key, milestone_index, true));
+		}
+		System::<T>::set_block_number(11u32.into());
+		let origin = T::ApproveOrigin::successful_origin();
+	}: _<T::Origin>(origin, round_index, project_key, (0..m).collect())
+	verify {
+		assert_last_event::<T>(Event::ProposalApproved(round_index, project_key).into());
+	}
+
+	withdraw {
+		let m in 1 .. T::MaxMilestonesPerProject::get();
+		let (owner, project_key, round_index) = open_project::<T>(m);
+		approve_milestones::<T>(&owner, project_key, round_index, m);
+		System::<T>::set_block_number(T::DisputePeriod::get() + 11u32.into());
+	}: _(RawOrigin::Signed(owner), round_index, project_key)
+
+	raise_dispute {
+		let (owner, project_key, round_index) = rejected_milestone::<T>();
+	}: _(RawOrigin::Signed(owner.clone()), round_index, project_key, 0)
+	verify {
+		assert_last_event::<T>(Event::DisputeRaised(project_key, 0, owner, T::DisputeBond::get()).into());
+	}
+
+	resolve_dispute {
+		let (owner, project_key, round_index) = rejected_milestone::<T>();
+		assert_ok!(Pallet::<T>::raise_dispute(RawOrigin::Signed(owner).into(), round_index, project_key, 0));
+		let origin = T::DisputeOrigin::successful_origin();
+	}: _<T::Origin>(origin, project_key, 0, true)
+	verify {
+		assert_last_event::<T>(Event::DisputeResolved(project_key, 0, true).into());
+	}
+
+	cancel {
+		let (_, project_key, round_index) = open_project::<T>(1);
+		let origin = T::CancelOrigin::successful_origin();
+	}: _<T::Origin>(origin, round_index, project_key)
+	verify {
+		assert_last_event::<T>(Event::ProposalCanceled(round_index, project_key).into());
+	}
+
+	cancel_spam_project {
+		let (owner, project_key, _) = open_project::<T>(1);
+		let (_, deposit) = ProjectDeposits::<T>::get(project_key).unwrap();
+		let origin = T::CancelOrigin::successful_origin();
+	}: _<T::Origin>(origin, project_key)
+	verify {
+		assert_last_event::<T>(Event::ProjectDepositSlashed(project_key, owner, deposit).into());
+	}
+
+	// The worst case also closes the project
+	refund {
+		let (_, project_key, round_index) = open_project::<T>(1);
+		let caller = funded_account::<T>("contributor", 0);
+		contribute::<T>(&caller, project_key);
+		assert_ok!(Pallet::<T>::cancel(T::CancelOrigin::successful_origin(), round_index, project_key));
+	}: _(RawOrigin::Signed(caller), round_index, project_key)
+
+	refund_contributors {
+		let c in 1 .. MAX_CONTRIBUTORS;
+		let (_, project_key, round_index) = open_project::<T>(1);
+		for i in 0..c {
+			contribute::<T>(&funded_account::<T>("contributor", i), project_key);
+		}
+		assert_ok!(Pallet::<T>::cancel(T::CancelOrigin::successful_origin(), round_index, project_key));
+		let origin = T::CancelOrigin::successful_origin();
+	}: _<T::Origin>(origin, round_index, project_key, c)
+
+	finalize_round {
+		let p in 1 .. T::MaxProposalsPerRound::get();
+		let m in 1 .. T::MaxMilestonesPerProject::get();
+		let owner = funded_account::<T>("owner", 0);
+		let contributor = funded_account::<T>("contributor", 0);
+		let project_keys: Vec<ProjectIndex> = (0..p).map(|_| create_project::<T>(&owner, m)).collect();
+		let round_index = schedule_round::<T>(project_keys.clone(), m);
+		System::<T>::set_block_number(2u32.into());
+		assert_ok!(Pallet::<T>::fund(RawOrigin::Signed(contributor.clone()).into(), round_index, contribution_value::<T>()));
+		for project_key in project_keys {
+			contribute::<T>(&contributor, project_key);
+			for milestone_index in 0..m {
+				submit_milestone::<T>(&owner, project_key, milestone_index);
+				assert_ok!(Pallet::<T>::vote_on_milestone(RawOrigin::Signed(contributor.clone()).into(), project_key, milestone_index, true));
+			}
+		}
+		let now: T::BlockNumber = 10u32.into();
+		System::<T>::set_block_number(now);
+	}: {
+		Pallet::<T>::finalize_round(round_index, now);
+	}
+	verify {
+		assert_last_event::<T>(Event::RoundFinalized(round_index).into());
+	}
+
+	set_max_proposal_count_per_round {
+		let s in 1 .. T::MaxProposalsPerRound::get();
+		let origin = T::AdminOrigin::successful_origin();
+	}: _<T::Origin>(origin, s)
+
+	set_withdrawal_expiration {
+		let origin = T::AdminOrigin::successful_origin();
+	}: _<T::Origin>(origin, 100u32.into())
+
+	set_default_voting_rules {
+		let rules = DefaultVotingRules::<T>::get();
+		let origin = T::AdminOrigin::successful_origin();
+	}: _<T::Origin>(origin, rules.clone())
+	verify {
+		assert_last_event::<T>(Event::VotingRulesSet(None, rules).into());
+	}
+
+	set_project_voting_rules {
+		let owner = funded_account::<T>("owner", 0);
+		let project_key = create_project::<T>(&owner, 1);
+		let rules = DefaultVotingRules::<T>::get();
+		let origin = T::AdminOrigin::successful_origin();
+	}: _<T::Origin>(origin, project_key, rules.clone())
+	verify {
+		assert_last_event::<T>(Event::VotingRulesSet(Some(project_key), rules).into());
+	}
+
+	set_is_identity_required {
+		let origin = T::AdminOrigin::successful_origin();
+	}: _<T::Origin>(origin, true)
+
+	// Sending to the treasury is the more expensive destination
+	expire_withdrawal {
+		let (owner, project_key, round_index) = open_project::<T>(T::MaxMilestonesPerProject::get());
+		approve_milestones::<T>(&owner, project_key, round_index, T::MaxMilestonesPerProject::get());
+		UnclaimedFundsPolicy::<T>::put(UnclaimedFundsDestination::Treasury);
+		let expiry_block = Proposals::<T>::get(round_index, project_key).unwrap().withdrawal_expiration + 1u32.into();
+	}: {
+		Pallet::<T>::expire_withdrawal(round_index, project_key, expiry_block);
+	}
+	verify {
+		assert!(ExpiredMilestones::<T>::contains_key(project_key, T::MaxMilestonesPerProject::get() - 1));
+	}
+
+	claim_returned_funds {
+		let (owner, project_key, round_index) = open_project::<T>(1);
+		approve_milestones::<T>(&owner, project_key, round_index, 1);
+		let expiry_block = Proposals::<T>::get(round_index, project_key).unwrap().withdrawal_expiration + 1u32.into();
+		Pallet::<T>::expire_withdrawal(round_index, project_key, expiry_block);
+		// The worst case credits the contributor with a batch for every milestone
+		let max_batches = T::MaxMilestonesPerProject::get();
+		let returned = ReturnedFunds::<T>::get(project_key)[0].clone();
+		let batch = ReturnedFundsBatch {
+			amount: returned.amount / max_batches.into(),
+			raised_funds: returned.raised_funds,
+		};
+		let batches: Vec<_> = (0..max_batches).map(|_| batch.clone()).collect();
+		let batches: BoundedVec<_, T::MaxMilestonesPerProject> = batches.try_into().unwrap();
+		ReturnedFunds::<T>::insert(project_key, batches);
+		let caller = funded_account::<T>("contributor", 0);
+	}: _(RawOrigin::Signed(caller.clone()), project_key)
+	verify {
+		assert_eq!(ReturnedFundsClaimed::<T>::get(project_key, &caller).settled_batches, max_batches);
+	}
+
+	set_unclaimed_funds_policy {
+		let origin = T::AdminOrigin::successful_origin();
+	}: _<T::Origin>(origin, UnclaimedFundsDestination::Treasury)
+	verify {
+		assert_last_event::<T>(Event::UnclaimedFundsPolicySet(UnclaimedFundsDestination::Treasury).into());
+	}
+
+	// The worst case passes over rounds that ended but are still waiting to be finalised
+	processing_round {
+		let u in 1 .. T::MaxUpcomingRounds::get();
+		open_project_after_rounds::<T>(1, u);
+		let now = System::<T>::block_number();
+	}: {
+		assert!(Pallet::<T>::processing_round(now).is_some());
+	}
+}
+
+impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
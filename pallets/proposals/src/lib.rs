@@ -8,16 +8,32 @@
 use frame_support::traits::GenesisBuild;
 use frame_support::{
 	pallet_prelude::*, PalletId,
-	log,
-	traits::{Currency, ReservableCurrency, ExistenceRequirement, WithdrawReasons},
+	log, transactional,
+	traits::{fungibles, Currency, ReservableCurrency, ExistenceRequirement, OnUnbalanced, WithdrawReasons},
+	traits::schedule::{DispatchTime, ScheduleNamed, HARD_DEADLINE},
 };
 use codec::{Encode, Decode};
-use sp_std::prelude::*;
+use sp_std::{convert::TryInto, prelude::*};
+use sp_core::H256;
 use integer_sqrt::IntegerSquareRoot;
-use sp_runtime::{traits::{AccountIdConversion,Saturating},Perbill};
+use sp_runtime::{traits::{AccountIdConversion,Dispatchable,Saturating,SaturatedConversion,UniqueSaturatedInto,Zero},Perbill};
 pub use pallet::*;
 use scale_info::TypeInfo;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(test)]
+mod mock;
 
+#[cfg(test)]
+mod xcm_mock;
+
+#[cfg(test)]
+mod xcm_tests;
 
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
@@ -25,7 +41,12 @@ mod benchmarking;
 pub mod weights;
 pub use weights::*;
 
-const MAX_STRING_FIELD_LENGTH: usize = 256;
+pub mod migration;
+
+/// Prefix of the names of the scheduler tasks opening and closing rounds
+const ROUND_TASK_ID: [u8; 8] = *b"imbround";
+/// IPFS CIDs are well under this, whatever their version and base
+const MAX_CID_LENGTH: u32 = 128;
 
 #[frame_support::pallet]
 pub mod pallet {
@@ -43,30 +64,150 @@ pub mod pallet {
 
 		type Currency: ReservableCurrency<Self::AccountId>;
 
+		/// Identifies the assets projects can raise funds in
+		type AssetId: Member + Parameter + Copy + MaybeSerializeDeserialize;
+
+		/// The assets projects can raise funds in, besides `Currency`
+		type Assets: fungibles::Transfer<Self::AccountId, AssetId = Self::AssetId>;
+
 		type MaxProposalsPerRound: Get<u32>;
 
+		/// How many milestones a project can have
+		type MaxMilestonesPerProject: Get<u32>;
+
+		/// How many rounds can be scheduled ahead of being finalised
+		type MaxUpcomingRounds: Get<u32>;
+
 		type MaxWithdrawalExpiration: Get<Self::BlockNumber>;
 
+		/// How long contributors can vote on a milestone once it has been submitted
+		type MilestoneVotingPeriod: Get<Self::BlockNumber>;
+
+		/// The share of votes, by weight, that must be in favour for a milestone to be approved
+		type MilestoneApprovalThreshold: Get<Perbill>;
+
+		/// The share of voting weight that must take part for a milestone vote to count, unless a project says otherwise
+		type MilestoneMinTurnout: Get<Perbill>;
+
+		/// The maximum weight `on_initialize` may spend finalising ended rounds and expiring withdrawals in one block
+		type MaxFinalizationWeight: Get<Weight>;
+
+		/// Where unclaimed funds go when the unclaimed funds policy sends them to the treasury
+		type Treasury: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+		/// The maximum length of a project's name and website, and of milestone names
+		type MaxStringLength: Get<u32>;
+
+		/// The maximum length of a logo or description stored on chain, rather than as an IPFS CID or hash
+		type MaxContentLength: Get<u32>;
+
+		/// The deposit reserved from the owner of every project, released when it completes
+		type ProjectDepositBase: Get<BalanceOf<Self>>;
+
+		/// The deposit reserved per byte of project stored on chain
+		type ProjectDepositPerByte: Get<BalanceOf<Self>>;
+
+		/// The origin allowed to schedule and cancel funding rounds
+		type ScheduleOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The origin allowed to approve milestones for withdrawal
+		type ApproveOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The origin allowed to cancel proposals and spam projects, and refund their contributors
+		type CancelOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The origin allowed to change the pallet's settings and voting rules
+		type AdminOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The origin that settles milestone disputes
+		type DisputeOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The bond reserved from whoever disputes a milestone outcome, slashed if the dispute is dismissed
+		type DisputeBond: Get<BalanceOf<Self>>;
+
+		/// How long after a round ends the milestone outcomes it decided can be disputed
+		type DisputePeriod: Get<Self::BlockNumber>;
+
+		/// The calls the scheduler dispatches to open and close rounds
+		type ScheduledCall: Parameter + Dispatchable<Origin = Self::Origin> + From<Call<Self>>;
+
+		/// The origin scheduled calls are dispatched with
+		type PalletsOrigin: From<frame_system::RawOrigin<Self::AccountId>>;
+
+		/// Opens and closes rounds at their start and end blocks
+		type Scheduler: ScheduleNamed<Self::BlockNumber, Self::ScheduledCall, Self::PalletsOrigin>;
+
+		/// Turns contributions into voting weight under the `VoteWeighting` a project chose
+		type VoteWeigher: VoteWeigher<Self::AccountId, BalanceOf<Self>>;
+
 		type WeightInfo: WeightInfo;
 	}
 
+	/// The current storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(8);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(PhantomData<T>);
 
 	#[pallet::storage]
 	#[pallet::getter(fn projects)]
-	pub type Projects<T: Config> = StorageMap<_, Identity, ProjectIndex, Project<T::AccountId, BalanceOf<T>, T::BlockNumber>, ValueQuery>;
+	pub type Projects<T: Config> = StorageMap<_, Identity, ProjectIndex, ProjectOf<T>, ValueQuery>;
 
 	#[pallet::storage]
     #[pallet::getter(fn user_votes)]
-    pub(super) type UserVotes<T: Config> = StorageMap<_, Identity, (T::AccountId, ProjectIndex, MilestoneIndex), bool, ValueQuery>;
+    pub(super) type UserVotes<T: Config> = StorageMap<_, Identity, (T::AccountId, ProjectIndex, MilestoneIndex), UserVote<BalanceOf<T>, T::BlockNumber>, OptionQuery>;
 
 
 	#[pallet::storage]
     #[pallet::getter(fn milestone_votes)]
     pub(super) type MilestoneVotes<T: Config> = StorageMap<_, Identity, (ProjectIndex, MilestoneIndex), Vote<BalanceOf<T>>, ValueQuery>;
 
+	#[pallet::storage]
+	#[pallet::getter(fn milestone_submissions)]
+	pub type MilestoneSubmissions<T: Config> = StorageMap<_, Identity, (ProjectIndex, MilestoneIndex), MilestoneSubmissionOf<T>, OptionQuery>;
+
+	/// Matching funds paid to each project from quadratic funding rounds
+	#[pallet::storage]
+	#[pallet::getter(fn project_matching_funds)]
+	pub type ProjectMatchingFunds<T: Config> = StorageMap<_, Identity, ProjectIndex, BalanceOf<T>, ValueQuery>;
+
+	/// Matching funds held by the pallet that belong to no round: the pools of cancelled rounds, what a round could not
+	/// distribute and what closed projects did not spend. They are added to the pool of the next round to be finalised.
+	#[pallet::storage]
+	#[pallet::getter(fn unallocated_matching_fund)]
+	pub type UnallocatedMatchingFund<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn refunds)]
+	pub type Refunds<T: Config> = StorageMap<_, Blake2_128Concat, (ProjectIndex, T::AccountId), BalanceOf<T>, OptionQuery>;
+
+	/// The raw storage key of the last contribution visited by `refund_contributors`, so the next page resumes after it
+	#[pallet::storage]
+	#[pallet::getter(fn refund_cursors)]
+	pub type RefundCursors<T: Config> = StorageMap<_, Twox64Concat, ProjectIndex, Vec<u8>, OptionQuery>;
+
+	/// What the owner has been paid for each milestone of a project
+	#[pallet::storage]
+	#[pallet::getter(fn milestone_payouts)]
+	pub type MilestonePayouts<T: Config> = StorageDoubleMap<_, Twox64Concat, ProjectIndex, Twox64Concat, MilestoneIndex, MilestonePayoutOf<T>, OptionQuery>;
+
+	/// The account each project's deposit was reserved from, and how much
+	#[pallet::storage]
+	#[pallet::getter(fn project_deposits)]
+	pub type ProjectDeposits<T: Config> = StorageMap<_, Twox64Concat, ProjectIndex, (T::AccountId, BalanceOf<T>), OptionQuery>;
+
+	/// Milestone outcomes contested by the project owner or a contributor, awaiting `DisputeOrigin`
+	#[pallet::storage]
+	#[pallet::getter(fn disputes)]
+	pub type Disputes<T: Config> = StorageDoubleMap<_, Twox64Concat, ProjectIndex, Twox64Concat, MilestoneIndex, DisputeOf<T>, OptionQuery>;
+
+	/// The account each project's owner has proposed to hand the project over to
+	#[pallet::storage]
+	#[pallet::getter(fn pending_owners)]
+	pub type PendingOwners<T: Config> = StorageMap<_, Twox64Concat, ProjectIndex, T::AccountId, OptionQuery>;
+
 	#[pallet::storage]
 	#[pallet::getter(fn project_count)]
 	pub type ProjectCount<T> = StorageValue<_, ProjectIndex, ValueQuery>;
@@ -79,14 +220,140 @@ pub mod pallet {
 	#[pallet::getter(fn round_count)]
 	pub type RoundCount<T> = StorageValue<_, RoundIndex, ValueQuery>;
 
+	/// The projects taking part in each round, in the order they were scheduled
+	#[pallet::storage]
+	#[pallet::getter(fn round_projects)]
+	pub type RoundProjects<T: Config> = StorageMap<_, Twox64Concat, RoundIndex, BoundedVec<ProjectIndex, T::MaxProposalsPerRound>, ValueQuery>;
+
+	/// The proposal of each project taking part in a round
+	#[pallet::storage]
+	#[pallet::getter(fn proposals)]
+	pub type Proposals<T: Config> = StorageDoubleMap<_, Twox64Concat, RoundIndex, Twox64Concat, ProjectIndex, ProposalOf<T>, OptionQuery>;
+
+	/// The total each account has contributed to a project, across all rounds
+	#[pallet::storage]
+	#[pallet::getter(fn contributions)]
+	pub type Contributions<T: Config> = StorageDoubleMap<_, Twox64Concat, ProjectIndex, Blake2_128Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+
+	/// What each account contributed to a project during a single round, used for quadratic funding
+	#[pallet::storage]
+	#[pallet::getter(fn round_contributions)]
+	pub type RoundContributions<T: Config> = StorageDoubleMap<_, Twox64Concat, (RoundIndex, ProjectIndex), Blake2_128Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+
+	/// Rounds to finalise, keyed by their end block
+	#[pallet::storage]
+	#[pallet::getter(fn round_ends)]
+	pub type RoundEnds<T: Config> = StorageMap<_, Twox64Concat, T::BlockNumber, Vec<RoundIndex>, ValueQuery>;
+
+	/// Ended rounds waiting for finalisation, carried over when a block runs out of weight
+	#[pallet::storage]
+	#[pallet::getter(fn finalization_queue)]
+	pub type FinalizationQueue<T> = StorageValue<_, Vec<RoundIndex>, ValueQuery>;
+
+	/// Rounds that have been scheduled and are neither cancelled nor finalised, in the order they start
+	#[pallet::storage]
+	#[pallet::getter(fn upcoming_rounds)]
+	pub type UpcomingRounds<T: Config> = StorageValue<_, BoundedVec<RoundIndex, T::MaxUpcomingRounds>, ValueQuery>;
+
+	/// The upcoming rounds each project has a proposal in that has not been cancelled
+	#[pallet::storage]
+	#[pallet::getter(fn active_rounds)]
+	pub type ActiveRounds<T> = StorageDoubleMap<_, Twox64Concat, ProjectIndex, Twox64Concat, RoundIndex, (), OptionQuery>;
+
+	/// The end block of the last finalised round, before which no new round may start
+	#[pallet::storage]
+	#[pallet::getter(fn last_finalized_round_end)]
+	pub type LastFinalizedRoundEnd<T: Config> = StorageValue<_, T::BlockNumber, OptionQuery>;
+
+	/// Approved proposals whose withdrawal window closes at a block, expired at the start of that block
+	#[pallet::storage]
+	#[pallet::getter(fn withdrawal_expiries)]
+	pub type WithdrawalExpiries<T: Config> = StorageMap<_, Twox64Concat, T::BlockNumber, Vec<(RoundIndex, ProjectIndex)>, ValueQuery>;
+
+	/// What was still unclaimed of each milestone when its withdrawal window closed
+	#[pallet::storage]
+	#[pallet::getter(fn expired_milestones)]
+	pub type ExpiredMilestones<T: Config> = StorageDoubleMap<_, Twox64Concat, ProjectIndex, Twox64Concat, MilestoneIndex, BalanceOf<T>, OptionQuery>;
+
+	/// Unclaimed funds of a project returned to its contributors, a batch for each withdrawal window that closed
+	/// Each batch is claimed with `claim_returned_funds`, pro rata between those who had contributed when it was returned.
+	#[pallet::storage]
+	#[pallet::getter(fn returned_funds)]
+	pub type ReturnedFunds<T: Config> = StorageMap<_, Twox64Concat, ProjectIndex, BoundedVec<ReturnedFundsBatchOf<T>, T::MaxMilestonesPerProject>, ValueQuery>;
+
+	/// How many of a project's returned batches each contributor has been credited with, and what is left to claim
+	#[pallet::storage]
+	#[pallet::getter(fn returned_funds_claimed)]
+	pub type ReturnedFundsClaimed<T: Config> = StorageDoubleMap<_, Twox64Concat, ProjectIndex, Blake2_128Concat, T::AccountId, ReturnedFundsClaimOf<T>, ValueQuery>;
+
+	/// Where unclaimed funds go once a withdrawal window closes
+	#[pallet::storage]
+	#[pallet::getter(fn unclaimed_funds_policy)]
+	pub type UnclaimedFundsPolicy<T> = StorageValue<_, UnclaimedFundsDestination, ValueQuery>;
+
+	#[pallet::type_value]
+	pub fn MaxProposalCountPerRoundOnEmpty<T: Config>() -> u32 {
+		T::MaxProposalsPerRound::get()
+	}
+
+	/// How many projects a round may hold, up to `MaxProposalsPerRound` when genesis did not set it
 	#[pallet::storage]
 	#[pallet::getter(fn max_proposal_count_per_round)]
-	pub type MaxProposalCountPerRound<T> = StorageValue<_, u32, ValueQuery>;
+	pub type MaxProposalCountPerRound<T: Config> = StorageValue<_, u32, ValueQuery, MaxProposalCountPerRoundOnEmpty<T>>;
 
 	#[pallet::storage]
 	#[pallet::getter(fn withdrawal_expiration)]
 	pub type WithdrawalExpiration<T> = StorageValue<_, BlockNumberFor<T>, ValueQuery>;
 
+	#[pallet::type_value]
+	pub fn DefaultVotingRulesOnEmpty<T: Config>() -> VotingRules {
+		VotingRules {
+			min_turnout: T::MilestoneMinTurnout::get(),
+			approval_threshold: T::MilestoneApprovalThreshold::get(),
+			allow_abstain: false,
+		}
+	}
+
+	/// The voting rules given to new projects
+	#[pallet::storage]
+	#[pallet::getter(fn default_voting_rules)]
+	pub type DefaultVotingRules<T: Config> = StorageValue<_, VotingRules, ValueQuery, DefaultVotingRulesOnEmpty<T>>;
+
+	/// The currency each project raises its funds in, the native currency unless the owner chose an asset
+	#[pallet::storage]
+	#[pallet::getter(fn project_currencies)]
+	pub type ProjectCurrencies<T: Config> = StorageMap<_, Twox64Concat, ProjectIndex, FundingCurrencyOf<T>, ValueQuery>;
+
+	/// The least each project must have raised by the end of a round to proceed
+	#[pallet::storage]
+	#[pallet::getter(fn project_soft_caps)]
+	pub type ProjectSoftCaps<T: Config> = StorageMap<_, Twox64Concat, ProjectIndex, BalanceOf<T>, ValueQuery>;
+
+	/// The voting rules a project's milestones are tallied with, fixed when the project is created
+	#[pallet::storage]
+	#[pallet::getter(fn project_voting_rules)]
+	pub type ProjectVotingRules<T> = StorageMap<_, Identity, ProjectIndex, VotingRules, OptionQuery>;
+
+	/// How each project turns contributions into voting weight, chosen by the owner before it is scheduled
+	#[pallet::storage]
+	#[pallet::getter(fn project_vote_weightings)]
+	pub type ProjectVoteWeightings<T: Config> = StorageMap<_, Twox64Concat, ProjectIndex, VoteWeightingOf<T>, ValueQuery>;
+
+	/// The vote weighting each project had when it was scheduled in a round, used for contributions made during it
+	#[pallet::storage]
+	#[pallet::getter(fn round_vote_weightings)]
+	pub type RoundVoteWeightings<T: Config> = StorageDoubleMap<_, Twox64Concat, RoundIndex, Twox64Concat, ProjectIndex, VoteWeightingOf<T>, ValueQuery>;
+
+	/// The voting weight of each contributor to a project, updated whenever they contribute or vote
+	#[pallet::storage]
+	#[pallet::getter(fn vote_weights)]
+	pub type VoteWeights<T: Config> = StorageDoubleMap<_, Twox64Concat, ProjectIndex, Blake2_128Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+
+	/// The voting weight of all contributors to a project, which turnout is measured against
+	#[pallet::storage]
+	#[pallet::getter(fn total_vote_weights)]
+	pub type TotalVoteWeights<T: Config> = StorageMap<_, Twox64Concat, ProjectIndex, BalanceOf<T>, ValueQuery>;
+
 	#[pallet::storage]
 	#[pallet::getter(fn is_identity_required)]
 	pub type IsIdentityRequired<T> = StorageValue<_, bool, ValueQuery>;
@@ -130,52 +397,165 @@ pub mod pallet {
 		ProposalWithdrawn(RoundIndex, ProjectIndex, BalanceOf<T>),
 		ProposalApproved(RoundIndex, ProjectIndex),
 		RoundCanceled(RoundIndex),
-		FundSucceed(),
+		FundSucceed(T::AccountId, RoundIndex, BalanceOf<T>),
+		MatchingFundDistributed(RoundIndex, ProjectIndex, BalanceOf<T>),
+		/// Unallocated matching funds were added to the pool of a round as it was finalised
+		MatchingFundCarriedOver(RoundIndex, BalanceOf<T>),
 		RoundFinalized(RoundIndex),
+		/// A round reached its start block and is open for contributions
+		RoundStarted(RoundIndex),
+		/// A round reached its end block and is closed for contributions
+		RoundEnded(RoundIndex),
 		VoteComplete(T::AccountId, ProjectIndex, MilestoneIndex, bool, T::BlockNumber),
+		VoteAbstained(T::AccountId, ProjectIndex, MilestoneIndex, T::BlockNumber),
+		VoteChanged(T::AccountId, ProjectIndex, MilestoneIndex, VoteOption, T::BlockNumber),
+		VoteRetracted(T::AccountId, ProjectIndex, MilestoneIndex, T::BlockNumber),
+		MilestoneApproved(ProjectIndex, MilestoneIndex, VotingRules),
+		MilestoneRejected(ProjectIndex, MilestoneIndex, VotingRules),
+		VotingRulesSet(Option<ProjectIndex>, VotingRules),
+		ContributorRefunded(T::AccountId, ProjectIndex, BalanceOf<T>),
+		MilestoneSubmitted(ProjectIndex, MilestoneIndex, T::BlockNumber),
+		ProjectClosed(ProjectIndex),
+		MilestonePaid(ProjectIndex, MilestoneIndex, BalanceOf<T>),
+		UnclaimedFundsReturned(RoundIndex, ProjectIndex, BalanceOf<T>, UnclaimedFundsDestination),
+		ReturnedFundsClaimed(T::AccountId, ProjectIndex, BalanceOf<T>),
+		UnclaimedFundsPolicySet(UnclaimedFundsDestination),
+		ProjectDepositReserved(ProjectIndex, T::AccountId, BalanceOf<T>),
+		ProjectDepositReleased(ProjectIndex, T::AccountId, BalanceOf<T>),
+		ProjectDepositSlashed(ProjectIndex, T::AccountId, BalanceOf<T>),
+		/// A project moved from the first state to the second
+		ProjectStateChanged(ProjectIndex, ProjectState, ProjectState),
+		ProjectUpdated(ProjectIndex),
+		MilestonesUpdated(ProjectIndex),
+		/// The owner proposed a new owner, or withdrew the proposal
+		OwnerProposed(ProjectIndex, Option<T::AccountId>),
+		/// The ownership of a project moved from the first account to the second
+		OwnershipTransferred(ProjectIndex, T::AccountId, T::AccountId),
+		/// A round ended with the project short of its soft cap, having raised this much
+		SoftCapMissed(RoundIndex, ProjectIndex, BalanceOf<T>),
+		FundingCurrencySet(ProjectIndex, FundingCurrencyOf<T>),
+		VoteWeightingSet(ProjectIndex, VoteWeightingOf<T>),
+		/// The account disputed the outcome of a milestone, reserving the bond
+		DisputeRaised(ProjectIndex, MilestoneIndex, T::AccountId, BalanceOf<T>),
+		/// A milestone dispute was upheld, overturning its outcome, or dismissed
+		DisputeResolved(ProjectIndex, MilestoneIndex, bool),
 	}
 
 	// Errors inform users that something went wrong.
 	#[pallet::error]
 	pub enum Error<T> {
+		AbstainNotAllowed,
+		AlreadyRefunded,
+		/// The caller cannot dispute this milestone outcome
+		CannotDispute,
+		/// The milestone outcome is already disputed
+		DisputeAlreadyRaised,
+		/// The milestone outcome can no longer be disputed
+		DisputePeriodOver,
+		/// The round's milestones can still be disputed
+		DisputePeriodNotOver,
 		EndBlockNumberInvalid,
 		EndTooEarly,
+		/// The project has raised its required funds
+		HardCapReached,
 		IdentityNeeded,
 		InvalidParam,
 		InvalidAccount,
 		InvalidProjectIndexes,
+		/// The project is not in a state that allows this
+		InvalidProjectState,
 		MilestonesTotalPercentageMustEqual100,
+		MilestoneAlreadySubmitted,
+		MilestoneApproved,
+		MilestoneDoesNotExist,
+		/// The milestone is not voted on in the current round
+		MilestoneNotProposed,
+		MilestoneNotSubmitted,
+		MilestoneVotingClosed,
+		NothingToClaim,
 		NotEnoughFund,
 		/// Error names should be descriptive.
 		NoneValue,
 		NoActiveRound,
 		NoActiveProposal,
+		NoDispute,
+		/// The caller has not been proposed as the new owner of the project
+		NotPendingOwner,
 		/// There was an overflow.
 		///
 		Overflow,
 		OnlyContributorsCanVote,
+		OnlyContributorsCanRefund,
 		ProposalAmountExceed,
 		ProposalCanceled,
 		ProposalWithdrawn,
 		ProposalApproved,
 		ProposalNotApproved,
+		ProposalNotCanceled,
+		ProjectClosed,
+		/// Withdrawals are paused while a milestone of the project is disputed
+		ProjectDisputed,
 		ParamLimitExceed,
 		RoundStarted,
 		RoundNotEnded,
+		/// The round has already been closed
+		RoundEnded,
 		RoundNotProcessing,
 		RoundCanceled,
 		RoundFinalized,
 		RoundNotFinalized,
+		/// The scheduler could not take the round's opening or closing
+		RoundNotScheduled,
 		/// Errors should have helpful documentation associated with them.
 		StorageOverflow,
 		StartBlockNumberInvalid,
 		StartBlockNumberTooSmall,
+		/// `MaxUpcomingRounds` rounds are already waiting to be finalised
+		TooManyUpcomingRounds,
+		/// A project can have at most `MaxMilestonesPerProject` milestones
+		TooManyMilestones,
+		/// The asset does not exist
+		UnknownAsset,
 		VoteAlreadyExists,
+		VoteDoesNotExist,
 		WithdrawalExpirationExceed,
 	}
 
 	#[pallet::hooks]
-	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_runtime_upgrade() -> Weight {
+			migration::v1::migrate::<T>()
+				.saturating_add(migration::v2::migrate::<T>())
+				.saturating_add(migration::v3::migrate::<T>())
+				.saturating_add(migration::v4::migrate::<T>())
+				.saturating_add(migration::v5::migrate::<T>())
+				.saturating_add(migration::v6::migrate::<T>())
+				.saturating_add(migration::v7::migrate::<T>())
+				.saturating_add(migration::v8::migrate::<T>())
+		}
+
+		fn integrity_test() {
+			assert!(T::MaxContentLength::get() >= MAX_CID_LENGTH, "MaxContentLength must fit an IPFS CID");
+			assert!(DefaultVotingRulesOnEmpty::<T>::get().is_valid(), "MilestoneApprovalThreshold must be below 100%");
+			assert!(
+				T::MaxWithdrawalExpiration::get() > T::DisputePeriod::get(),
+				"MaxWithdrawalExpiration must leave time to withdraw once the DisputePeriod is over"
+			);
+
+			let full_round_weight = <T as Config>::WeightInfo::finalize_round(T::MaxProposalsPerRound::get(), T::MaxMilestonesPerProject::get())
+				.saturating_add(T::DbWeight::get().reads_writes(4, 2));
+			assert!(
+				full_round_weight <= T::MaxFinalizationWeight::get(),
+				"MaxFinalizationWeight must fit finalising a round of MaxProposalsPerRound proposals"
+			);
+		}
+
+		/// Finalise the rounds that have ended and expire closed withdrawal windows, as far as `MaxFinalizationWeight` allows
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let weight = Self::finalize_ended_rounds(now);
+			Self::expire_withdrawals(now, weight)
+		}
+	}
 
 	// Dispatchable functions allows users to interact with the pallet and invoke state changes.
 	// These functions materialize as "extrinsics", which are often compared to transactions.
@@ -183,59 +563,28 @@ pub mod pallet {
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		/// Create project
-		#[pallet::weight(<T as Config>::WeightInfo::create_project())]
-		pub fn create_project(origin: OriginFor<T>, name: Vec<u8>, logo: Vec<u8>, description: Vec<u8>, website: Vec<u8>, proposed_milestones: Vec<ProposedMilestone>, required_funds: BalanceOf<T>) -> DispatchResultWithPostInfo {
+		/// required_funds: the hard cap, contributions beyond it are trimmed
+		/// soft_cap: the least the project must raise by the end of a round to proceed, otherwise it is closed for refunds
+		#[pallet::weight(<T as Config>::WeightInfo::create_project(proposed_milestones.len() as u32))]
+		pub fn create_project(origin: OriginFor<T>, name: Vec<u8>, logo: ProjectContent<Vec<u8>>, description: ProjectContent<Vec<u8>>, website: Vec<u8>, proposed_milestones: Vec<ProposedMilestone>, required_funds: BalanceOf<T>, soft_cap: BalanceOf<T>) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
-
-			// Check if identity is required
-			let is_identity_needed = IsIdentityRequired::<T>::get();
-			if is_identity_needed {
-				let identity = pallet_identity::Pallet::<T>::identity(who.clone()).ok_or(Error::<T>::IdentityNeeded)?;
-				let mut is_found_judgement = false;
-				for judgement in identity.judgements.iter() {
-					if judgement.1 == pallet_identity::Judgement::Reasonable || judgement.1 == pallet_identity::Judgement::KnownGood {
-						is_found_judgement = true;
-						break;
-					}
-				}
-				ensure!(is_found_judgement, Error::<T>::IdentityNeeded);
-			}
+			Self::ensure_identity(&who)?;
 
 			// Validation
 			ensure!(name.len() > 0, Error::<T>::InvalidParam);
-			ensure!(logo.len() > 0, Error::<T>::InvalidParam);
-			ensure!(description.len() > 0, Error::<T>::InvalidParam);
 			ensure!(website.len() > 0, Error::<T>::InvalidParam);
+			let name = Self::bound_string(name)?;
+			let logo = Self::bound_content(logo)?;
+			let description = Self::bound_content(description)?;
+			let website = Self::bound_string(website)?;
+			ensure!(required_funds > (0 as u32).into(), Error::<T>::InvalidParam);
+			ensure!(soft_cap <= required_funds, Error::<T>::InvalidParam);
 
-			// let mut total_percentage = 0;
-			// for milestone in milestones.iter() {
-			// 	log::info!("*********************** percentage for this milestone is {:?} ***********************",milestone.percentage_to_unlock);
-			// 	total_percentage += milestone.percentage_to_unlock;
-			// }
-			// ensure!(total_percentage == 100, Error::<T>::MilestonesTotalPercentageMustEqual100);
-
-			// ensure!(name.len() <= MAX_STRING_FIELD_LENGTH, Error::<T>::ParamLimitExceed);
-			// ensure!(logo.len() <= MAX_STRING_FIELD_LENGTH, Error::<T>::ParamLimitExceed);
-			// ensure!(description.len() <= MAX_STRING_FIELD_LENGTH, Error::<T>::ParamLimitExceed);
-			// ensure!(website.len() <= MAX_STRING_FIELD_LENGTH, Error::<T>::ParamLimitExceed);
-			
 			let project_key = ProjectCount::<T>::get();
 			let next_project_key = project_key.checked_add(1).ok_or(Error::<T>::Overflow)?;
 
-			let mut milestones = Vec::new();
-			let mut milestone_index:u32 = 0;
-
 			// Fill in the proposals structure in advance
-			for milestone in proposed_milestones {
-				milestones.push(Milestone {
-					project_key: project_key,
-					milestone_index:milestone_index,
-					name: milestone.name,
-					percentage_to_unlock: milestone.percentage_to_unlock,
-					is_approved: false,
-				});
-				milestone_index = milestone_index.checked_add(1).ok_or(Error::<T>::Overflow)?;
-			}
+			let milestones = Self::new_milestones(project_key, proposed_milestones)?;
  
 			// Create a proposal 
 			let project = Project {
@@ -244,81 +593,248 @@ pub mod pallet {
 				description: description,
 				website: website,
 				milestones: milestones,
-				contributions: Vec::new(),
+				raised_funds: (0 as u32).into(),
 				required_funds: required_funds,
 				withdrawn_funds:(0 as u32).into(), 
-				owner: who,
+				owner: who.clone(),
 				create_block_number: <frame_system::Pallet<T>>::block_number(),
+				state: ProjectState::Draft,
 			};
 
+			// Reserve a deposit covering what the project stores on chain
+			let deposit = Self::project_deposit(&project);
+			<T as Config>::Currency::reserve(&who, deposit)?;
+
 			// Add proposal to list
 			<Projects<T>>::insert(project_key, project);
+			ProjectVotingRules::<T>::insert(project_key, DefaultVotingRules::<T>::get());
+			ProjectSoftCaps::<T>::insert(project_key, soft_cap);
+			ProjectDeposits::<T>::insert(project_key, (who.clone(), deposit));
 			ProjectCount::<T>::put(next_project_key);
 
+			Self::deposit_event(Event::ProjectDepositReserved(project_key, who, deposit));
 			Self::deposit_event(Event::ProjectCreated(project_key));
 
 			Ok(().into())
 		}
 
+		/// Update the name, logo, description and website of a project
+		/// Only the owner can do this, and only before the project is scheduled in a round.
+		/// The deposit is topped up or partly released to match the new size of the project.
+		#[pallet::weight(<T as Config>::WeightInfo::update_project())]
+		#[transactional]
+		pub fn update_project(origin: OriginFor<T>, project_key: ProjectIndex, name: Vec<u8>, logo: ProjectContent<Vec<u8>>, description: ProjectContent<Vec<u8>>, website: Vec<u8>) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let mut project = Self::ensure_editable(&who, project_key)?;
+
+			ensure!(name.len() > 0, Error::<T>::InvalidParam);
+			ensure!(website.len() > 0, Error::<T>::InvalidParam);
+			project.name = Self::bound_string(name)?;
+			project.logo = Self::bound_content(logo)?;
+			project.description = Self::bound_content(description)?;
+			project.website = Self::bound_string(website)?;
+
+			Self::adjust_deposit(project_key, &project)?;
+			<Projects<T>>::insert(project_key, project);
+
+			Self::deposit_event(Event::ProjectUpdated(project_key));
+
+			Ok(().into())
+		}
+
+		/// Replace the milestones of a project
+		/// Only the owner can do this, and only before the project is scheduled in a round.
+		/// Submissions made for the previous milestones are dropped.
+		#[pallet::weight(<T as Config>::WeightInfo::update_milestones(proposed_milestones.len() as u32))]
+		#[transactional]
+		pub fn update_milestones(origin: OriginFor<T>, project_key: ProjectIndex, proposed_milestones: Vec<ProposedMilestone>) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let mut project = Self::ensure_editable(&who, project_key)?;
+
+			let milestones = Self::new_milestones(project_key, proposed_milestones)?;
+			let previous_milestones = sp_std::mem::replace(&mut project.milestones, milestones);
+			for milestone in previous_milestones {
+				let key = (project_key, milestone.milestone_index);
+				MilestoneSubmissions::<T>::remove(key);
+				<MilestoneVotes<T>>::remove(key);
+			}
+
+			Self::adjust_deposit(project_key, &project)?;
+			<Projects<T>>::insert(project_key, project);
+
+			Self::deposit_event(Event::MilestonesUpdated(project_key));
+
+			Ok(().into())
+		}
+
+		/// Choose the currency a project raises its funds in
+		/// Only the owner can do this, and only before the project is scheduled in a round.
+		/// Contributions, withdrawals and refunds are then all made in that currency.
+		/// Projects raising an asset take no share of matching funds, which are in the native currency.
+		#[pallet::weight(<T as Config>::WeightInfo::set_funding_currency())]
+		pub fn set_funding_currency(origin: OriginFor<T>, project_key: ProjectIndex, currency: FundingCurrencyOf<T>) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			Self::ensure_editable(&who, project_key)?;
+
+			match currency {
+				FundingCurrency::Native => ProjectCurrencies::<T>::remove(project_key),
+				FundingCurrency::Asset(asset_id) => {
+					// Only existing assets have a minimum balance
+					let minimum_balance = <T::Assets as fungibles::Inspect<AccountIdOf<T>>>::minimum_balance(asset_id);
+					ensure!(!minimum_balance.is_zero(), Error::<T>::UnknownAsset);
+					ProjectCurrencies::<T>::insert(project_key, currency);
+				},
+			}
+
+			Self::deposit_event(Event::FundingCurrencySet(project_key, currency));
+
+			Ok(().into())
+		}
+
+		/// Choose how a project's contributions turn into voting weight
+		/// Every round the project is scheduled in keeps the weighting it had at the time.
+		#[pallet::weight(<T as Config>::WeightInfo::set_vote_weighting())]
+		pub fn set_vote_weighting(origin: OriginFor<T>, project_key: ProjectIndex, weighting: VoteWeightingOf<T>) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			Self::ensure_editable(&who, project_key)?;
+
+			if let VoteWeighting::Capped(cap) = weighting {
+				ensure!(!cap.is_zero(), Error::<T>::InvalidParam);
+			}
+			ProjectVoteWeightings::<T>::insert(project_key, weighting);
+
+			Self::deposit_event(Event::VoteWeightingSet(project_key, weighting));
+
+			Ok(().into())
+		}
+
+		/// Propose a new owner for a project, who becomes the owner once they accept
+		/// `None` withdraws a pending proposal.
+		#[pallet::weight(<T as Config>::WeightInfo::propose_owner())]
+		pub fn propose_owner(origin: OriginFor<T>, project_key: ProjectIndex, new_owner: Option<T::AccountId>) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let project_exists = Projects::<T>::contains_key(project_key);
+			ensure!(project_exists, Error::<T>::InvalidProjectIndexes);
+			let project = Projects::<T>::get(project_key);
+			ensure!(who == project.owner, Error::<T>::InvalidAccount);
+			ensure!(!project.state.is_closed(), Error::<T>::ProjectClosed);
+
+			match new_owner.clone() {
+				Some(new_owner) => PendingOwners::<T>::insert(project_key, new_owner),
+				None => PendingOwners::<T>::remove(project_key),
+			}
+
+			Self::deposit_event(Event::OwnerProposed(project_key, new_owner));
+
+			Ok(().into())
+		}
+
+		/// Accept the ownership of a project proposed to the caller
+		/// The project's deposit is reserved from the new owner and released to the previous one.
+		#[pallet::weight(<T as Config>::WeightInfo::accept_ownership())]
+		#[transactional]
+		pub fn accept_ownership(origin: OriginFor<T>, project_key: ProjectIndex) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let pending_owner = PendingOwners::<T>::get(project_key).ok_or(Error::<T>::NotPendingOwner)?;
+			ensure!(who == pending_owner, Error::<T>::NotPendingOwner);
+			let mut project = Projects::<T>::get(project_key);
+			ensure!(!project.state.is_closed(), Error::<T>::ProjectClosed);
+			Self::ensure_identity(&who)?;
+
+			if let Some((depositor, deposit)) = ProjectDeposits::<T>::get(project_key) {
+				<T as Config>::Currency::reserve(&who, deposit)?;
+				<T as Config>::Currency::unreserve(&depositor, deposit);
+				ProjectDeposits::<T>::insert(project_key, (who.clone(), deposit));
+			}
+
+			let previous_owner = sp_std::mem::replace(&mut project.owner, who.clone());
+			<Projects<T>>::insert(project_key, project);
+			PendingOwners::<T>::remove(project_key);
+
+			Self::deposit_event(Event::OwnershipTransferred(project_key, previous_owner, who));
+
+			Ok(().into())
+		}
+
 		/// Schedule a round
-		/// proposal_indexes: the proposals were selected for this round
-		#[pallet::weight(<T as Config>::WeightInfo::schedule_round(MaxProposalCountPerRound::<T>::get()))]
-		pub fn schedule_round(origin: OriginFor<T>, start: T::BlockNumber, end: T::BlockNumber, project_key: ProjectIndex, milestone_indexes: Vec<MilestoneIndex>) -> DispatchResultWithPostInfo {
-			ensure_root(origin)?;
+		/// projects: the projects selected for this round, each with the milestones to vote on
+		/// The scheduler announces the round's start and end with `RoundStarted` and `RoundEnded` at those blocks.
+		#[pallet::weight(<T as Config>::WeightInfo::schedule_round(projects.len() as u32))]
+		#[transactional]
+		pub fn schedule_round(origin: OriginFor<T>, start: T::BlockNumber, end: T::BlockNumber, projects: Vec<(ProjectIndex, Vec<MilestoneIndex>)>) -> DispatchResultWithPostInfo {
+			T::ScheduleOrigin::ensure_origin(origin)?;
 			let now = <frame_system::Pallet<T>>::block_number();
 
 			// The number of items cannot exceed the maximum
-			// ensure!(project_keyes.len() as u32 <= MaxProposalCountPerRound::<T>::get(), Error::<T>::ProposalAmountExceed);
+			ensure!(projects.len() > 0, Error::<T>::InvalidProjectIndexes);
+			ensure!(projects.len() as u32 <= MaxProposalCountPerRound::<T>::get(), Error::<T>::ProposalAmountExceed);
 			// The end block must be greater than the start block
 			ensure!(end > start, Error::<T>::EndTooEarly);
 			// Both the starting block number and the ending block number must be greater than the current number of blocks
 			// ensure!(start > now, Error::<T>::StartBlockNumberInvalid);
 			ensure!(end > now, Error::<T>::EndBlockNumberInvalid);
 
-			// project_key should be smaller than project count
+			// Every project_key should be smaller than project count and appear only once
 			let project_count = ProjectCount::<T>::get();
-			ensure!(project_key < project_count, Error::<T>::InvalidProjectIndexes);
-
-			// Find the last valid round
-			let mut last_valid_round: Option<RoundOf::<T>> = None;
-			let index = RoundCount::<T>::get();
-
-			for _i in (0..index).rev() {
-				let round = <Rounds<T>>::get(index-1).unwrap();
-				if !round.is_canceled {
-					last_valid_round = Some(round);
-					break;
-				}
+			for (i, (project_key, milestone_indexes)) in projects.iter().enumerate() {
+				ensure!(*project_key < project_count, Error::<T>::InvalidProjectIndexes);
+				let is_duplicate = projects[..i].iter().any(|(other_key, _)| other_key == project_key);
+				ensure!(!is_duplicate, Error::<T>::InvalidProjectIndexes);
+				let project = Projects::<T>::get(project_key);
+				ensure!(!project.state.is_closed(), Error::<T>::ProjectClosed);
+				Self::ensure_open_milestones(&project, milestone_indexes)?;
 			}
 
-			// The start time must be greater than the end time of the last valid round
-			match last_valid_round {
-				Some(round) => {
-					ensure!(start > round.end, Error::<T>::StartBlockNumberTooSmall);
-				},
-				None => {}
+			// The start time must be greater than the end time of the last round that was not cancelled
+			let mut upcoming_rounds = UpcomingRounds::<T>::get();
+			let last_round_end = match upcoming_rounds.last() {
+				Some(round_index) => <Rounds<T>>::get(round_index).map(|round| round.end),
+				None => LastFinalizedRoundEnd::<T>::get(),
+			};
+			if let Some(last_round_end) = last_round_end {
+				ensure!(start > last_round_end, Error::<T>::StartBlockNumberTooSmall);
 			}
 
+			let index = RoundCount::<T>::get();
 			let next_index = index.checked_add(1).ok_or(Error::<T>::Overflow)?;
-
-			let round = RoundOf::<T>::new(start, end, project_key, milestone_indexes.clone());
-
-			for milestone_index in milestone_indexes {
-				// Initialise voting
-				let vote = Vote {
-					yay: (0 as u32).into(),
-					nay: (0 as u32).into(),
-					is_approved: false
-				};
-				let vote_lookup_key = (project_key, milestone_index);
-				<MilestoneVotes<T>>::insert(vote_lookup_key,vote);
+			upcoming_rounds.try_push(index).map_err(|_| Error::<T>::TooManyUpcomingRounds)?;
+
+			let mut project_keys: BoundedVec<ProjectIndex, T::MaxProposalsPerRound> = Default::default();
+			for (project_key, milestone_indexes) in projects {
+				project_keys.try_push(project_key).map_err(|_| Error::<T>::ProposalAmountExceed)?;
+				let milestone_indexes: BoundedMilestoneIndexesOf<T> = milestone_indexes.try_into().map_err(|_| Error::<T>::TooManyMilestones)?;
+				<Proposals<T>>::insert(index, project_key, ProposalOf::<T>::new(milestone_indexes));
+				ActiveRounds::<T>::insert(project_key, index, ());
+				RoundVoteWeightings::<T>::insert(index, project_key, ProjectVoteWeightings::<T>::get(project_key));
+
+				// Projects that already have approved milestones stay in them
+				let mut project = Projects::<T>::get(project_key);
+				if project.state != ProjectState::InMilestones {
+					Self::set_state(project_key, &mut project, ProjectState::Fundraising)?;
+					<Projects<T>>::insert(project_key, project);
+				}
 			}
 
+			let round = RoundOf::<T>::new(start, end);
+
 			// Add proposal round to list
 			<Rounds<T>>::insert(index, Some(round));
+			<RoundProjects<T>>::insert(index, project_keys);
 			RoundCount::<T>::put(next_index);
+			RoundEnds::<T>::append(end, index);
+			UpcomingRounds::<T>::put(upcoming_rounds);
+
+			// A round starting in the past is opened straight away
+			let has_started = start <= now;
+			if !has_started {
+				Self::schedule_round_task(index, start, true)?;
+			}
+			Self::schedule_round_task(index, end, false)?;
 
 			Self::deposit_event(Event::RoundCreated(index));
+			if has_started {
+				Self::deposit_event(Event::RoundStarted(index));
+			}
 
 			Ok(().into())
 		}
@@ -327,9 +843,8 @@ pub mod pallet {
 		/// This round must have not started yet
 		#[pallet::weight(<T as Config>::WeightInfo::cancel_round())]
 		pub fn cancel_round(origin: OriginFor<T>, round_index: RoundIndex) -> DispatchResultWithPostInfo {
-			ensure_root(origin)?;
+			T::ScheduleOrigin::ensure_origin(origin)?;
 			let now = <frame_system::Pallet<T>>::block_number();
-			let count = RoundCount::<T>::get();
 			let mut round = <Rounds<T>>::get(round_index).ok_or(Error::<T>::NoActiveRound)?;
 
 			// Ensure current round is not started
@@ -337,187 +852,252 @@ pub mod pallet {
 			// This round cannot be cancelled
 			ensure!(!round.is_canceled, Error::<T>::RoundCanceled);
 
+			// The matching pool is carried over to the next round to be finalised
+			Self::release_matching_fund(round.matching_fund);
+			round.matching_fund = (0 as u32).into();
 			round.is_canceled = true;
 			<Rounds<T>>::insert(round_index, Some(round.clone()));
+			UpcomingRounds::<T>::mutate(|upcoming_rounds| upcoming_rounds.retain(|index| *index != round_index));
 
-			Self::deposit_event(Event::RoundCanceled(count-1));
-
-			Ok(().into())
-		}
-
-		/// Vote on a milestone
-		#[pallet::weight(<T as Config>::WeightInfo::contribute())]
-		pub fn vote_on_milestone(origin: OriginFor<T>, project_key: ProjectIndex, milestone_index: MilestoneIndex, approve_milestone: bool) -> DispatchResultWithPostInfo {
-			let who = ensure_signed(origin)?;
-
-			let project_count = ProjectCount::<T>::get();
-			ensure!(project_key < project_count, Error::<T>::InvalidParam);
-			let now = <frame_system::Pallet<T>>::block_number();
-			
-			// round list must be not none
-			let round_index = RoundCount::<T>::get();
-			ensure!(round_index > 0, Error::<T>::NoActiveRound);
-
-			// Find processing round
-			let mut processing_round: Option<RoundOf::<T>> = None;
-			for i in (0..round_index).rev() {
-				let round = <Rounds<T>>::get(i).unwrap();
-				if !round.is_canceled && round.start < now && round.end > now {
-					processing_round = Some(round);
+			// The round has not started, so neither of its tasks has run
+			for is_start in [true, false].iter() {
+				if T::Scheduler::cancel_named(Self::round_task_id(round_index, *is_start)).is_err() {
+					log::warn!("No scheduled task to cancel for round {}", round_index);
 				}
 			}
-			let mut round = processing_round.ok_or(Error::<T>::RoundNotProcessing)?;
 
-			// Find proposal by index
-			let mut found_proposal: Option<&mut ProposalOf::<T>> = None;
-			for proposal in round.proposals.iter_mut() {
-				if proposal.project_key == project_key {
-					found_proposal = Some(proposal);
-					break;
+			// Projects that were only raising funds in this round go back to where they were
+			for project_key in RoundProjects::<T>::get(round_index) {
+				ActiveRounds::<T>::remove(project_key, round_index);
+				let mut project = Projects::<T>::get(project_key);
+				if project.state == ProjectState::Fundraising && !Self::is_in_open_round(project_key, round_index) {
+					Self::set_state(project_key, &mut project, Self::idle_state(&project))?;
+					<Projects<T>>::insert(project_key, project);
 				}
 			}
 
-			let proposal = found_proposal.ok_or(Error::<T>::NoActiveProposal)?;
+			Self::deposit_event(Event::RoundCanceled(round_index));
 
-			let project_exists = Projects::<T>::contains_key(project_key.clone());
-			ensure!(project_exists, Error::<T>::InvalidProjectIndexes);
-			let project = Projects::<T>::get(project_key);
+			Ok(().into())
+		}
 
-			ensure!(!proposal.is_canceled, Error::<T>::ProposalCanceled);
-			let mut existing_contributer = false;
-			let mut contribution_amount: BalanceOf<T>  = (0 as u32).into();
-
-			// Find previous contribution by account_id
-			// If you have contributed before, then add to that contribution. Otherwise join the list.
-			for contribution in project.contributions.clone().iter_mut() {
-				if contribution.account_id == who {
-					existing_contributer = true;
-					contribution_amount = contribution.value;
-					break;
-				}
-			}
+		/// Announce that a round is open for contributions, dispatched by the scheduler at its start block
+		#[pallet::weight(<T as Config>::WeightInfo::start_round())]
+		pub fn start_round(origin: OriginFor<T>, round_index: RoundIndex) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+			let round = <Rounds<T>>::get(round_index).ok_or(Error::<T>::NoActiveRound)?;
+			ensure!(!round.is_canceled, Error::<T>::RoundCanceled);
+			let now = <frame_system::Pallet<T>>::block_number();
+			ensure!(round.start <= now, Error::<T>::RoundNotProcessing);
+			// Rounds starting in the past were announced when they were scheduled
+			ensure!(round.start == now, Error::<T>::RoundStarted);
 
-			ensure!(existing_contributer, Error::<T>::OnlyContributorsCanVote);
-			let vote_lookup_key = (who.clone(), project_key, milestone_index);
+			Self::deposit_event(Event::RoundStarted(round_index));
 
-			let vote_exists = UserVotes::<T>::contains_key(vote_lookup_key.clone());
-			ensure!(!vote_exists, Error::<T>::VoteAlreadyExists);
-			
-			<UserVotes<T>>::insert(vote_lookup_key,approve_milestone);
+			Ok(().into())
+		}
 
-			let current_vote = <MilestoneVotes<T>>::get((project_key, milestone_index));
+		/// Announce that a round is closed for contributions, dispatched by the scheduler at its end block
+		#[pallet::weight(<T as Config>::WeightInfo::end_round())]
+		pub fn end_round(origin: OriginFor<T>, round_index: RoundIndex) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+			let round = <Rounds<T>>::get(round_index).ok_or(Error::<T>::NoActiveRound)?;
+			ensure!(!round.is_canceled, Error::<T>::RoundCanceled);
+			let now = <frame_system::Pallet<T>>::block_number();
+			ensure!(round.end <= now, Error::<T>::RoundNotEnded);
+			ensure!(round.end == now, Error::<T>::RoundEnded);
 
-			if approve_milestone {
-				let updated_vote = Vote {
-					yay: current_vote.yay + contribution_amount,
-					nay: current_vote.nay,
-					is_approved: current_vote.is_approved
-				};
-				<MilestoneVotes<T>>::insert((project_key, milestone_index),updated_vote)
+			Self::deposit_event(Event::RoundEnded(round_index));
 
-			} else {
-				let updated_vote = Vote {
-					yay: current_vote.yay,
-					nay: current_vote.nay + contribution_amount,
-					is_approved: current_vote.is_approved
-				};
-				<MilestoneVotes<T>>::insert((project_key, milestone_index),updated_vote)
-			}
+			Ok(().into())
+		}
+
+		/// Add funds to the matching pool of a round
+		/// The pool is distributed between the round's proposals with quadratic funding when the round ends.
+		#[pallet::weight(<T as Config>::WeightInfo::fund())]
+		pub fn fund(origin: OriginFor<T>, round_index: RoundIndex, value: BalanceOf<T>) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			ensure!(value > (0 as u32).into(), Error::<T>::InvalidParam);
 
+			let mut round = <Rounds<T>>::get(round_index).ok_or(Error::<T>::NoActiveRound)?;
+			ensure!(!round.is_canceled, Error::<T>::RoundCanceled);
+			ensure!(!round.is_finalized, Error::<T>::RoundFinalized);
+
+			<T as Config>::Currency::transfer(
+				&who,
+				&Self::account_id(),
+				value,
+				ExistenceRequirement::AllowDeath
+			)?;
 
-			<Rounds<T>>::insert(round_index-1, Some(round));
-			Self::deposit_event(Event::VoteComplete(who, project_key, milestone_index, approve_milestone, now));
+			round.matching_fund = round.matching_fund.saturating_add(value);
+			<Rounds<T>>::insert(round_index, Some(round));
+
+			Self::deposit_event(Event::FundSucceed(who, round_index, value));
 
 			Ok(().into())
 		}
 
-		/// Contribute a proposal
-		#[pallet::weight(<T as Config>::WeightInfo::contribute())]
-		pub fn contribute(origin: OriginFor<T>, project_key: ProjectIndex, value: BalanceOf<T>) -> DispatchResultWithPostInfo { 
+		/// Submit a milestone for review
+		/// The project owner provides evidence (a hash or URI) and voting on the milestone opens immediately.
+		/// Milestones are submitted in a round that votes on them, and voting closes with the round at the latest.
+		/// A milestone that was not approved can be submitted again once voting on it has closed, starting a fresh vote.
+		#[pallet::weight(<T as Config>::WeightInfo::submit_milestone()
+			.saturating_add(<T as Config>::WeightInfo::processing_round(T::MaxUpcomingRounds::get())))]
+		pub fn submit_milestone(origin: OriginFor<T>, project_key: ProjectIndex, milestone_index: MilestoneIndex, evidence: Vec<u8>) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
-			ensure!(value > (0 as u32).into(), Error::<T>::InvalidParam);
-			let project_count = ProjectCount::<T>::get();
-			ensure!(project_key < project_count, Error::<T>::InvalidParam);
 			let now = <frame_system::Pallet<T>>::block_number();
-			
-			// round list must be not none
-			let round_index = RoundCount::<T>::get();
-			ensure!(round_index > 0, Error::<T>::NoActiveRound);
-
-			// Find processing round
-			let mut processing_round: Option<RoundOf::<T>> = None;
-			for i in (0..round_index).rev() {
-				let round = <Rounds<T>>::get(i).unwrap();
-				if !round.is_canceled && round.start < now && round.end > now {
-					processing_round = Some(round);
-				}
-			}
 
-			let mut round = processing_round.ok_or(Error::<T>::RoundNotProcessing)?;
+			ensure!(evidence.len() > 0, Error::<T>::InvalidParam);
+			let evidence = Self::bound_string(evidence)?;
 
-			// Find proposal by index
-			let mut found_proposal: Option<&mut ProposalOf::<T>> = None;
-			for proposal in round.proposals.iter_mut() {
-				if proposal.project_key == project_key {
-					found_proposal = Some(proposal);
-					break;
-				}
-			}
+			let project_exists = Projects::<T>::contains_key(project_key);
+			ensure!(project_exists, Error::<T>::InvalidProjectIndexes);
+			let project = Projects::<T>::get(project_key);
+
+			// Only project owner can submit milestones
+			ensure!(who == project.owner, Error::<T>::InvalidAccount);
+			ensure!(!project.state.is_closed(), Error::<T>::ProjectClosed);
 
-			let proposal = found_proposal.ok_or(Error::<T>::NoActiveProposal)?;
+			let milestone = project.milestones.iter()
+				.find(|milestone| milestone.milestone_index == milestone_index)
+				.ok_or(Error::<T>::MilestoneDoesNotExist)?;
+			ensure!(!milestone.is_approved, Error::<T>::MilestoneApproved);
+			ensure!(project.state != ProjectState::Draft, Error::<T>::InvalidProjectState);
+
+			let (round_index, round) = Self::processing_round(now).ok_or(Error::<T>::RoundNotProcessing)?;
+			let proposal = <Proposals<T>>::get(round_index, project_key).ok_or(Error::<T>::NoActiveProposal)?;
 			ensure!(!proposal.is_canceled, Error::<T>::ProposalCanceled);
+			ensure!(proposal.milestone_indexes.contains(&milestone_index), Error::<T>::MilestoneNotProposed);
 
-			// Find previous contribution by account_id
-			// If you have contributed before, then add to that contribution. Otherwise join the list.
-			let mut found_contribution: Option<&mut ContributionOf::<T>> = None;
-			for contribution in proposal.contributions.iter_mut() {
-				if contribution.account_id == who {
-					found_contribution = Some(contribution);
-					break;
-				}
+			let submission_key = (project_key, milestone_index);
+			if let Some(submission) = MilestoneSubmissions::<T>::get(submission_key) {
+				let is_under_review = now <= submission.voting_end || Disputes::<T>::contains_key(project_key, milestone_index);
+				ensure!(!is_under_review, Error::<T>::MilestoneAlreadySubmitted);
 			}
 
-			match found_contribution {
-				Some(contribution) => {
-					contribution.value += value;
-				},
-				None => {
-					proposal.contributions.push(ContributionOf::<T> {
-						account_id: who.clone(),
-						value: value,
-					});
-				}
-			}
+			// The round is tallied once it ends, so voting must have closed by then
+			let last_round_block = round.end.saturating_sub(1u32.into());
+			let voting_end = now.saturating_add(T::MilestoneVotingPeriod::get()).min(last_round_block);
+			MilestoneSubmissions::<T>::insert(submission_key, MilestoneSubmission {
+				evidence,
+				submitted_at: now,
+				voting_end,
+			});
 
-			let project_exists = Projects::<T>::contains_key(project_key.clone());
-			ensure!(project_exists, Error::<T>::InvalidProjectIndexes);
-
-			let project = Projects::<T>::get(project_key);
-			// Update project withdrawn funds
-			let updated_project = Project {
-				name: project.name,
-				logo: project.logo,
-				description: project.description,
-				website: project.website,
-				milestones: project.milestones,
-				contributions:proposal.contributions.clone(),
-				required_funds: project.required_funds,
-				withdrawn_funds: project.withdrawn_funds,
-				owner: project.owner,
-				create_block_number: project.create_block_number,
+			// Initialise voting, votes cast on an earlier submission no longer count
+			let vote = Vote {
+				yay: (0 as u32).into(),
+				nay: (0 as u32).into(),
+				abstain: (0 as u32).into(),
+				is_approved: false
 			};
-			// Add proposal to list
-			<Projects<T>>::insert(project_key, updated_project);
+			<MilestoneVotes<T>>::insert(submission_key, vote);
 
-			// Transfer contribute to proposal account
-			<T as Config>::Currency::transfer(
-				&who,
-				&Self::project_account_id(project_key),
-				value,
-				ExistenceRequirement::AllowDeath
-			)?;
+			Self::deposit_event(Event::MilestoneSubmitted(project_key, milestone_index, voting_end));
+
+			Ok(().into())
+		}
+
+		/// Vote on a milestone
+		#[pallet::weight(<T as Config>::WeightInfo::vote_on_milestone()
+			.saturating_add(<T as Config>::WeightInfo::processing_round(T::MaxUpcomingRounds::get())))]
+		pub fn vote_on_milestone(origin: OriginFor<T>, project_key: ProjectIndex, milestone_index: MilestoneIndex, approve_milestone: bool) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let vote_option = if approve_milestone { VoteOption::Yay } else { VoteOption::Nay };
+			Self::do_vote(who, project_key, milestone_index, vote_option)?;
+
+			Ok(().into())
+		}
+
+		/// Abstain from voting on a milestone
+		/// Abstentions count towards turnout but not towards approval, if the project's voting rules allow them.
+		#[pallet::weight(<T as Config>::WeightInfo::abstain_on_milestone()
+			.saturating_add(<T as Config>::WeightInfo::processing_round(T::MaxUpcomingRounds::get())))]
+		pub fn abstain_on_milestone(origin: OriginFor<T>, project_key: ProjectIndex, milestone_index: MilestoneIndex) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			Self::do_vote(who, project_key, milestone_index, VoteOption::Abstain)?;
+
+			Ok(().into())
+		}
+
+		/// Retract a vote on a milestone while voting is still open
+		#[pallet::weight(<T as Config>::WeightInfo::retract_vote()
+			.saturating_add(<T as Config>::WeightInfo::processing_round(T::MaxUpcomingRounds::get())))]
+		pub fn retract_vote(origin: OriginFor<T>, project_key: ProjectIndex, milestone_index: MilestoneIndex) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let (_, submission) = Self::ensure_can_vote(&who, project_key, milestone_index)?;
+			let now = <frame_system::Pallet<T>>::block_number();
+
+			let user_vote = Self::current_user_vote(&who, project_key, milestone_index, submission.submitted_at)
+				.ok_or(Error::<T>::VoteDoesNotExist)?;
+			UserVotes::<T>::remove((who.clone(), project_key, milestone_index));
+
+			let mut current_vote = <MilestoneVotes<T>>::get((project_key, milestone_index));
+			current_vote.remove(&user_vote);
+			<MilestoneVotes<T>>::insert((project_key, milestone_index), current_vote);
+
+			Self::deposit_event(Event::VoteRetracted(who, project_key, milestone_index, now));
+
+			Ok(().into())
+		}
+
+		/// Contribute a proposal
+		#[pallet::weight(<T as Config>::WeightInfo::contribute(T::MaxUpcomingRounds::get()))]
+		pub fn contribute(origin: OriginFor<T>, project_key: ProjectIndex, value: BalanceOf<T>) -> DispatchResultWithPostInfo { 
+			let who = ensure_signed(origin)?;
+			ensure!(value > (0 as u32).into(), Error::<T>::InvalidParam);
+			let project_count = ProjectCount::<T>::get();
+			ensure!(project_key < project_count, Error::<T>::InvalidParam);
+			let now = <frame_system::Pallet<T>>::block_number();
 			
-			<Rounds<T>>::insert(round_index-1, Some(round));
+			// round list must be not none
+			ensure!(RoundCount::<T>::get() > 0, Error::<T>::NoActiveRound);
+
+			let (processing_round_index, _) = Self::processing_round(now).ok_or(Error::<T>::RoundNotProcessing)?;
+
+			let mut proposal = <Proposals<T>>::get(processing_round_index, project_key).ok_or(Error::<T>::NoActiveProposal)?;
+			ensure!(!proposal.is_canceled, Error::<T>::ProposalCanceled);
+
+			let project_exists = Projects::<T>::contains_key(project_key.clone());
+			ensure!(project_exists, Error::<T>::InvalidProjectIndexes);
+
+			let mut project = Projects::<T>::get(project_key);
+			ensure!(!project.state.is_closed(), Error::<T>::ProjectClosed);
+			ensure!(
+				project.state == ProjectState::Fundraising || project.state == ProjectState::InMilestones,
+				Error::<T>::InvalidProjectState
+			);
+
+			// Contributions beyond the hard cap are trimmed, and rejected once it is reached
+			let remaining_funds = project.required_funds.saturating_sub(project.raised_funds);
+			ensure!(remaining_funds > (0 as u32).into(), Error::<T>::HardCapReached);
+			let value = value.min(remaining_funds);
+
+			// Transfer contribute to proposal account
+			Self::transfer_project_funds(project_key, &who, &Self::project_account_id(project_key), value)?;
+
+			// Funds returned before this contribution are shared at what was contributed until now
+			Self::settle_returned_funds(project_key, &who);
+
+			// If you have contributed before, then add to that contribution
+			let round_contribution_key = (processing_round_index, project_key);
+			let previous_round_contribution = RoundContributions::<T>::get(round_contribution_key, &who);
+			let round_contribution = previous_round_contribution.saturating_add(value);
+			proposal.replace_contribution(previous_round_contribution, round_contribution);
+			RoundContributions::<T>::insert(round_contribution_key, &who, round_contribution);
+			let contribution = Contributions::<T>::mutate(project_key, &who, |contribution| {
+				*contribution = contribution.saturating_add(value);
+				*contribution
+			});
+
+			// Weigh the whole contribution with the weighting of the round it is made in
+			let weighting = RoundVoteWeightings::<T>::get(processing_round_index, project_key);
+			Self::set_vote_weight(project_key, &who, T::VoteWeigher::vote_weight(&weighting, &who, contribution));
+
+			project.raised_funds = project.raised_funds.saturating_add(value);
+			<Projects<T>>::insert(project_key, project);
+			<Proposals<T>>::insert(processing_round_index, project_key, proposal);
 
 			Self::deposit_event(Event::ContributeSucceed(who, project_key, value, now));
 
@@ -526,12 +1106,11 @@ pub mod pallet {
 		
 		/// Approve project
 		/// If the project is approve, the project owner can withdraw funds
-		#[pallet::weight(<T as Config>::WeightInfo::approve())]
+		#[pallet::weight(<T as Config>::WeightInfo::approve(milestone_indexes.len() as u32))]
 		pub fn approve(origin: OriginFor<T>, round_index: RoundIndex, project_key: ProjectIndex, milestone_indexes:  Vec<MilestoneIndex>) -> DispatchResultWithPostInfo {
-			ensure_root(origin)?;
-			let mut round = <Rounds<T>>::get(round_index).ok_or(Error::<T>::NoActiveRound)?;
+			T::ApproveOrigin::ensure_origin(origin)?;
+			let round = <Rounds<T>>::get(round_index).ok_or(Error::<T>::NoActiveRound)?;
 			ensure!(!round.is_canceled, Error::<T>::RoundCanceled);
-			let proposals = &mut round.proposals;
 
 			// The round must have ended
 			let now = <frame_system::Pallet<T>>::block_number();
@@ -539,60 +1118,48 @@ pub mod pallet {
 			ensure!(round.end < now, Error::<T>::RoundNotEnded);
 
 			// Find proposal from list
-			let mut found_proposal: Option<&mut ProposalOf::<T>> = None;
-			for proposal in proposals.iter_mut() {
-				if proposal.project_key == project_key {
-					found_proposal = Some(proposal);
-					break;
-				}
-			}
-			let mut proposal = found_proposal.ok_or(Error::<T>::NoActiveProposal)?;
+			let mut proposal = <Proposals<T>>::get(round_index, project_key).ok_or(Error::<T>::NoActiveProposal)?;
 
 			// Can't let users vote in the cancered round
 			ensure!(!proposal.is_canceled, Error::<T>::ProposalCanceled);
-			// ensure!(!proposal.is_approved, Error::<T>::ProposalApproved);
+			let is_proposed = milestone_indexes.iter().all(|index| proposal.milestone_indexes.contains(index));
+			ensure!(is_proposed, Error::<T>::MilestoneNotProposed);
 
 			let project_exists = Projects::<T>::contains_key(project_key.clone());
 			ensure!(project_exists, Error::<T>::InvalidProjectIndexes);
 
-			let project = Projects::<T>::get(project_key);
-
-
-			let mut milestones = Vec::new();
-
-			// set is_approved
-			proposal.is_approved = true;
-
-			for mut milestone in project.milestones.into_iter() {
-				for index in milestone_indexes.clone().into_iter() {
-					if milestone.milestone_index == index {
-						let vote = <MilestoneVotes<T>>::get((project_key, index));
-						if vote.yay > vote.nay {
-							milestone.is_approved = true;
-						}
+			let mut updated_project = Projects::<T>::get(project_key);
+			ensure!(!updated_project.state.is_closed(), Error::<T>::ProjectClosed);
+			let rules = Self::voting_rules(project_key);
+			let total_vote_weight = TotalVoteWeights::<T>::get(project_key);
+
+			// Update project milestones, counting only the votes on submissions made during the round
+			let mut has_passed = false;
+			for index in milestone_indexes.iter() {
+				let is_submitted = MilestoneSubmissions::<T>::get((project_key, *index))
+					.map_or(false, |submission| Self::is_submitted_in(&round, &submission));
+				if let Some(milestone) = updated_project.milestone_mut(*index) {
+					let vote = <MilestoneVotes<T>>::get((project_key, *index));
+					if is_submitted && Self::is_vote_approved(&vote, &rules, total_vote_weight) {
+						milestone.is_approved = true;
+						has_passed = true;
 					}
 				}
-				milestones.push(milestone.clone());
+			}
+			ensure!(has_passed, Error::<T>::ProposalNotApproved);
+			if Self::idle_state(&updated_project) == ProjectState::InMilestones {
+				Self::set_state(project_key, &mut updated_project, ProjectState::InMilestones)?;
 			}
 
-			// for milestone in proposal.milestones.
-			proposal.withdrawal_expiration = now + <WithdrawalExpiration<T>>::get();
+			// The withdrawal window opens with the first approval, later calls do not extend it
+			if !proposal.is_approved {
+				proposal.is_approved = true;
+				proposal.withdrawal_expiration = now + Self::withdrawal_window();
+				Self::schedule_withdrawal_expiry(round_index, project_key, proposal.withdrawal_expiration);
+			}
 
-			<Rounds<T>>::insert(round_index, Some(round.clone()));
+			<Proposals<T>>::insert(round_index, project_key, proposal);
 
-			// Update project milestones
-			let updated_project = Project {
-				name: project.name,
-				logo: project.logo,
-				description: project.description,
-				website: project.website,
-				milestones: milestones,
-				contributions: project.contributions,
-				required_funds: project.required_funds,
-				withdrawn_funds: project.withdrawn_funds,
-				owner: project.owner,
-				create_block_number: project.create_block_number,
-			};
 			// Add proposal to list
 			<Projects<T>>::insert(project_key, updated_project);
 			Self::deposit_event(Event::ProposalApproved(round_index, project_key));
@@ -600,7 +1167,8 @@ pub mod pallet {
 		}
 
 		/// Withdraw
-		#[pallet::weight(<T as Config>::WeightInfo::withdraw())]
+		/// Pays the owner what is still due for each approved milestone, recording it in `MilestonePayouts`.
+		#[pallet::weight(<T as Config>::WeightInfo::withdraw(T::MaxMilestonesPerProject::get()))]
 		pub fn withdraw(origin: OriginFor<T>, round_index: RoundIndex, project_key: ProjectIndex) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
 			let now = <frame_system::Pallet<T>>::block_number();
@@ -612,47 +1180,40 @@ pub mod pallet {
 
 			let project = Projects::<T>::get(project_key);
 			ensure!(who == project.owner, Error::<T>::InvalidAccount);
-
-			let mut round = <Rounds<T>>::get(round_index).ok_or(Error::<T>::NoActiveRound)?;
-			let mut found_proposal: Option<&mut ProposalOf::<T>> = None;
-			for proposal in round.proposals.iter_mut() {
-				if proposal.project_key == project_key {
-					found_proposal = Some(proposal);
-					break;
+			ensure!(!project.state.is_closed(), Error::<T>::ProjectClosed);
+
+			let round = <Rounds<T>>::get(round_index).ok_or(Error::<T>::NoActiveRound)?;
+			let mut proposal = <Proposals<T>>::get(round_index, project_key).ok_or(Error::<T>::NoActiveProposal)?;
+			ensure!(proposal.is_approved, Error::<T>::ProposalNotApproved);
+			ensure!(now <= proposal.withdrawal_expiration, Error::<T>::WithdrawalExpirationExceed);
+			// Approvals are only paid once they can no longer be disputed
+			ensure!(now > round.end.saturating_add(T::DisputePeriod::get()), Error::<T>::DisputePeriodNotOver);
+			ensure!(Disputes::<T>::iter_prefix(project_key).next().is_none(), Error::<T>::ProjectDisputed);
+
+			// Pay out every milestone the proposal approved, less what the ledger says has already been paid for it
+			let total_contribution_amount: BalanceOf<T> = project.raised_funds.saturating_add(ProjectMatchingFunds::<T>::get(project_key));
+			let mut payouts = Vec::new();
+			let mut available_funds: BalanceOf<T> = (0 as u32).into();
+			let approved_milestones = project.milestones.iter()
+				.filter(|milestone| milestone.is_approved && proposal.milestone_indexes.contains(&milestone.milestone_index));
+			for milestone in approved_milestones {
+				// Milestones whose withdrawal window has closed are settled
+				if ExpiredMilestones::<T>::contains_key(project_key, milestone.milestone_index) {
+					continue;
 				}
-			}
-
-			let proposal = found_proposal.ok_or(Error::<T>::NoActiveProposal)?;
-			// ensure!(now <= proposal.withdrawal_expiration, Error::<T>::WithdrawalExpirationExceed);
-
-			// This proposal must not have distributed funds
-			// ensure!(proposal.is_approved, Error::<T>::ProposalNotApproved);
-			ensure!(!proposal.is_withdrawn, Error::<T>::ProposalWithdrawn);
-
-			// Calculate contribution amount
-			let mut total_contribution_amount: BalanceOf<T>  = (0 as u32).into();
-			for contribution in project.contributions.iter() {
-				let contribution_value = contribution.value;
-				total_contribution_amount += contribution_value;
-			}
-
-			let mut unlocked_funds: BalanceOf<T>  = (0 as u32).into();
-			for milestone in project.milestones.clone() {
-				if milestone.is_approved {
-					 unlocked_funds += (total_contribution_amount *  milestone.percentage_to_unlock.into())/100u32.into();
+				let paid = MilestonePayouts::<T>::get(project_key, milestone.milestone_index)
+					.map(|payout| payout.amount)
+					.unwrap_or_else(|| (0 as u32).into());
+				let due = Self::milestone_share(total_contribution_amount, milestone).saturating_sub(paid);
+				if due > (0 as u32).into() {
+					payouts.push((milestone.milestone_index, paid.saturating_add(due), due));
+					available_funds = available_funds.saturating_add(due);
 				}
 			}
-
-			let available_funds: BalanceOf<T> = unlocked_funds - project.withdrawn_funds;
-			ensure!(available_funds >  (0 as u32).into(), Error::<T>::InvalidParam);
+			ensure!(available_funds > (0 as u32).into(), Error::<T>::InvalidParam);
 
 			// Distribute contribution amount
-			let _ = <T as Config>::Currency::resolve_into_existing(&project.owner, <T as Config>::Currency::withdraw(
-				&Self::project_account_id(project_key),
-				available_funds,
-				WithdrawReasons::from(WithdrawReasons::TRANSFER),
-				ExistenceRequirement::AllowDeath,
-			)?);
+			Self::transfer_project_funds(project_key, &Self::project_account_id(project_key), &project.owner, available_funds)?;
 
 			// Update project withdrawn funds
 			let updated_project = Project {
@@ -661,50 +1222,122 @@ pub mod pallet {
 				description: project.description,
 				website: project.website,
 				milestones: project.milestones,
-				contributions:project.contributions,
+				raised_funds: project.raised_funds,
 				required_funds: project.required_funds,
-				withdrawn_funds: available_funds,
+				withdrawn_funds: project.withdrawn_funds.saturating_add(available_funds),
 				owner: project.owner,
 				create_block_number: project.create_block_number,
+				state: project.state,
 			};
 			// Add proposal to list
 			<Projects<T>>::insert(project_key, updated_project);
 
+			for (milestone_index, amount, due) in payouts {
+				MilestonePayouts::<T>::insert(project_key, milestone_index, MilestonePayout { amount, paid_at: now });
+				Self::deposit_event(Event::MilestonePaid(project_key, milestone_index, due));
+			}
+
 			// Set is_withdrawn
 			proposal.is_withdrawn = true;
-			proposal.withdrawal_expiration = now + <WithdrawalExpiration<T>>::get();
 
-			<Rounds<T>>::insert(round_index, Some(round.clone()));
+			<Proposals<T>>::insert(round_index, project_key, proposal);
 
 			Self::deposit_event(Event::ProposalWithdrawn(round_index, project_key, available_funds));
 
+			Self::complete_if_settled(project_key);
+
+			Ok(().into())
+		}
+
+		/// Dispute the outcome of a milestone vote, reserving `DisputeBond` from the caller
+		/// The owner can dispute a rejected milestone, and contributors who voted against it an approved one that
+		/// has not been paid, until `DisputePeriod` after the round ended.
+		#[pallet::weight(<T as Config>::WeightInfo::raise_dispute())]
+		pub fn raise_dispute(origin: OriginFor<T>, round_index: RoundIndex, project_key: ProjectIndex, milestone_index: MilestoneIndex) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let now = <frame_system::Pallet<T>>::block_number();
+
+			let round = <Rounds<T>>::get(round_index).ok_or(Error::<T>::NoActiveRound)?;
+			ensure!(round.is_finalized, Error::<T>::RoundNotFinalized);
+			ensure!(now <= round.end.saturating_add(T::DisputePeriod::get()), Error::<T>::DisputePeriodOver);
+
+			let proposal = <Proposals<T>>::get(round_index, project_key).ok_or(Error::<T>::NoActiveProposal)?;
+			ensure!(!proposal.is_canceled, Error::<T>::ProposalCanceled);
+			ensure!(proposal.milestone_indexes.contains(&milestone_index), Error::<T>::MilestoneDoesNotExist);
+
+			let project = Projects::<T>::get(project_key);
+			ensure!(!project.state.is_closed(), Error::<T>::ProjectClosed);
+			let milestone = project.milestones.iter()
+				.find(|milestone| milestone.milestone_index == milestone_index)
+				.ok_or(Error::<T>::MilestoneDoesNotExist)?;
+			ensure!(!Disputes::<T>::contains_key(project_key, milestone_index), Error::<T>::DisputeAlreadyRaised);
+
+			if milestone.is_approved {
+				// Only a vote on the submission the round approved counts
+				let voted_against = MilestoneSubmissions::<T>::get((project_key, milestone_index))
+					.and_then(|submission| Self::current_user_vote(&who, project_key, milestone_index, submission.submitted_at))
+					.map_or(false, |vote| vote.option == VoteOption::Nay);
+				ensure!(voted_against, Error::<T>::CannotDispute);
+				ensure!(!MilestonePayouts::<T>::contains_key(project_key, milestone_index), Error::<T>::CannotDispute);
+			} else {
+				// Owners can only dispute a rejection, so the milestone must have been put to a vote in the round
+				let was_tallied = MilestoneSubmissions::<T>::get((project_key, milestone_index))
+					.map_or(false, |submission| Self::is_submitted_in(&round, &submission));
+				ensure!(was_tallied, Error::<T>::MilestoneNotSubmitted);
+				ensure!(who == project.owner, Error::<T>::CannotDispute);
+			}
+
+			let bond = T::DisputeBond::get();
+			<T as Config>::Currency::reserve(&who, bond)?;
+			Disputes::<T>::insert(project_key, milestone_index, Dispute {
+				round_index,
+				raised_by: who.clone(),
+				bond,
+			});
+
+			Self::deposit_event(Event::DisputeRaised(project_key, milestone_index, who, bond));
+
+			Ok(().into())
+		}
+
+		/// Settle a milestone dispute, overturning the outcome if it is upheld
+		/// The bond is returned if the dispute is upheld, and slashed to the treasury if it is dismissed.
+		/// A withdrawal window that closed while the dispute was pending is opened again.
+		#[pallet::weight(<T as Config>::WeightInfo::resolve_dispute())]
+		#[transactional]
+		pub fn resolve_dispute(origin: OriginFor<T>, project_key: ProjectIndex, milestone_index: MilestoneIndex, uphold: bool) -> DispatchResultWithPostInfo {
+			T::DisputeOrigin::ensure_origin(origin)?;
+			let dispute = Disputes::<T>::take(project_key, milestone_index).ok_or(Error::<T>::NoDispute)?;
+
+			if uphold {
+				<T as Config>::Currency::unreserve(&dispute.raised_by, dispute.bond);
+				Self::overturn_milestone(dispute.round_index, project_key, milestone_index)?;
+			} else {
+				let (imbalance, _) = <T as Config>::Currency::slash_reserved(&dispute.raised_by, dispute.bond);
+				T::Treasury::on_unbalanced(imbalance);
+			}
+			Self::reopen_withdrawal_window(dispute.round_index, project_key);
+
+			Self::deposit_event(Event::DisputeResolved(project_key, milestone_index, uphold));
+
 			Ok(().into())
 		}
 
 		/// Cancel a problematic project
 		/// If the project is cancelled, users cannot donate to it, and project owner cannot withdraw funds.
+		/// Contributors can then be refunded what the owner has not withdrawn.
 		#[pallet::weight(<T as Config>::WeightInfo::cancel())]
+		#[transactional]
 		pub fn cancel(origin: OriginFor<T>, round_index: RoundIndex, project_key: ProjectIndex) -> DispatchResultWithPostInfo {
-			ensure_root(origin)?;
+			T::CancelOrigin::ensure_origin(origin)?;
 
-			let mut round = <Rounds<T>>::get(round_index).ok_or(Error::<T>::NoActiveRound)?;
+			let round = <Rounds<T>>::get(round_index).ok_or(Error::<T>::NoActiveRound)?;
 
 			// This round cannot be cancelled
 			ensure!(!round.is_canceled, Error::<T>::RoundCanceled);
 
-			let proposals = &mut round.proposals;
-
-			let mut found_proposal: Option<&mut ProposalOf::<T>> = None;
-
 			// Find proposal with project index
-			for proposal in proposals.iter_mut() {
-				if proposal.project_key == project_key {
-					found_proposal = Some(proposal);
-					break;
-				}
-			}
-
-			let proposal = found_proposal.ok_or(Error::<T>::NoActiveProposal)?;
+			let mut proposal = <Proposals<T>>::get(round_index, project_key).ok_or(Error::<T>::NoActiveProposal)?;
 
 			// This proposal must not have canceled
 			ensure!(!proposal.is_canceled, Error::<T>::ProposalCanceled);
@@ -712,18 +1345,103 @@ pub mod pallet {
 
 			proposal.is_canceled = true;
 
-			Rounds::<T>::insert(round_index, Some(round));
+			<Proposals<T>>::insert(round_index, project_key, proposal);
+			ActiveRounds::<T>::remove(project_key, round_index);
+
+			let mut project = Projects::<T>::get(project_key);
+			if !project.state.is_closed() {
+				Self::close_project(project_key, &mut project)?;
+				Self::release_deposit(project_key);
+			}
 
 			Self::deposit_event(Event::ProposalCanceled(round_index, project_key));
 
 			Ok(().into())
 		}
 
+		/// Cancel a project as spam, slashing its deposit to the treasury
+		/// The project is closed, so contributors can be refunded whatever the owner has not withdrawn.
+		#[pallet::weight(<T as Config>::WeightInfo::cancel_spam_project())]
+		#[transactional]
+		pub fn cancel_spam_project(origin: OriginFor<T>, project_key: ProjectIndex) -> DispatchResultWithPostInfo {
+			T::CancelOrigin::ensure_origin(origin)?;
+			let project_exists = Projects::<T>::contains_key(project_key);
+			ensure!(project_exists, Error::<T>::InvalidProjectIndexes);
+			let mut project = Projects::<T>::get(project_key);
+			ensure!(!project.state.is_closed(), Error::<T>::ProjectClosed);
+
+			Self::close_project(project_key, &mut project)?;
+
+			if let Some((depositor, deposit)) = ProjectDeposits::<T>::take(project_key) {
+				let (imbalance, _) = <T as Config>::Currency::slash_reserved(&depositor, deposit);
+				T::Treasury::on_unbalanced(imbalance);
+				Self::deposit_event(Event::ProjectDepositSlashed(project_key, depositor, deposit));
+			}
+
+			Ok(().into())
+		}
+
+		/// Refund the caller's contribution to a cancelled proposal
+		/// The refund is the caller's pro rata share of the funds the owner has not withdrawn.
+		#[pallet::weight(<T as Config>::WeightInfo::refund())]
+		#[transactional]
+		pub fn refund(origin: OriginFor<T>, round_index: RoundIndex, project_key: ProjectIndex) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let project = Self::ensure_refundable(round_index, project_key)?;
+
+			ensure!(Contributions::<T>::contains_key(project_key, &who), Error::<T>::OnlyContributorsCanRefund);
+			let contribution_value = Contributions::<T>::get(project_key, &who);
+			ensure!(!Refunds::<T>::contains_key((project_key, who.clone())), Error::<T>::AlreadyRefunded);
+
+			Self::do_refund(project_key, &project, who, contribution_value)?;
+
+			Ok(().into())
+		}
+
+		/// Refund up to `limit` contributors of a cancelled proposal, resuming where the previous call stopped
+		/// Contributors are visited in storage order. Those that have already been refunded are skipped.
+		#[pallet::weight(<T as Config>::WeightInfo::refund_contributors(*limit))]
+		#[transactional]
+		pub fn refund_contributors(origin: OriginFor<T>, round_index: RoundIndex, project_key: ProjectIndex, limit: u32) -> DispatchResultWithPostInfo {
+			T::CancelOrigin::ensure_origin(origin)?;
+			let project = Self::ensure_refundable(round_index, project_key)?;
+
+			let mut contributions = match RefundCursors::<T>::get(project_key) {
+				Some(last_key) => Contributions::<T>::iter_prefix_from(project_key, last_key),
+				None => Contributions::<T>::iter_prefix(project_key),
+			};
+
+			let mut visited: u32 = 0;
+			let mut last_account = None;
+			while visited < limit {
+				let (account_id, contribution_value) = match contributions.next() {
+					Some(contribution) => contribution,
+					None => break,
+				};
+				visited += 1;
+				last_account = Some(account_id.clone());
+				if Refunds::<T>::contains_key((project_key, account_id.clone())) {
+					continue;
+				}
+				Self::do_refund(project_key, &project, account_id, contribution_value)?;
+			}
+
+			// Only keep the cursor while there are contributions left to visit
+			match last_account {
+				Some(account_id) if visited == limit && contributions.next().is_some() => {
+					RefundCursors::<T>::insert(project_key, Contributions::<T>::hashed_key_for(project_key, &account_id));
+				}
+				_ => RefundCursors::<T>::remove(project_key),
+			}
+
+			Ok(().into())
+		}
+
 		/// Set max proposal count per round
 		#[pallet::weight(<T as Config>::WeightInfo::set_max_proposal_count_per_round(T::MaxProposalsPerRound::get()))]
 		pub fn set_max_proposal_count_per_round(origin: OriginFor<T>, max_proposal_count_per_round: u32) -> DispatchResultWithPostInfo {
-			ensure_root(origin)?;
-			ensure!(max_proposal_count_per_round > 0 || max_proposal_count_per_round <= T::MaxProposalsPerRound::get(), Error::<T>::ParamLimitExceed);
+			T::AdminOrigin::ensure_origin(origin)?;
+			ensure!(max_proposal_count_per_round > 0 && max_proposal_count_per_round <= T::MaxProposalsPerRound::get(), Error::<T>::ParamLimitExceed);
 			MaxProposalCountPerRound::<T>::put(max_proposal_count_per_round);
 
 			Ok(().into())
@@ -732,25 +1450,223 @@ pub mod pallet {
 		/// Set withdrawal expiration
 		#[pallet::weight(<T as Config>::WeightInfo::set_withdrawal_expiration())]
 		pub fn set_withdrawal_expiration(origin: OriginFor<T>, withdrawal_expiration: T::BlockNumber) -> DispatchResultWithPostInfo {
-			ensure_root(origin)?;
+			T::AdminOrigin::ensure_origin(origin)?;
 			ensure!(withdrawal_expiration > (0 as u32).into(), Error::<T>::InvalidParam);
+			ensure!(withdrawal_expiration <= T::MaxWithdrawalExpiration::get(), Error::<T>::WithdrawalExpirationExceed);
 			<WithdrawalExpiration<T>>::put(withdrawal_expiration);
 
 			Ok(().into())
 		}
 
+		/// Set the voting rules given to newly created projects
+		#[pallet::weight(<T as Config>::WeightInfo::set_default_voting_rules())]
+		pub fn set_default_voting_rules(origin: OriginFor<T>, rules: VotingRules) -> DispatchResultWithPostInfo {
+			T::AdminOrigin::ensure_origin(origin)?;
+			ensure!(rules.is_valid(), Error::<T>::InvalidParam);
+			DefaultVotingRules::<T>::put(rules.clone());
+			Self::deposit_event(Event::VotingRulesSet(None, rules));
+
+			Ok(().into())
+		}
+
+		/// Set the voting rules of an existing project
+		#[pallet::weight(<T as Config>::WeightInfo::set_project_voting_rules())]
+		pub fn set_project_voting_rules(origin: OriginFor<T>, project_key: ProjectIndex, rules: VotingRules) -> DispatchResultWithPostInfo {
+			T::AdminOrigin::ensure_origin(origin)?;
+			let project_exists = Projects::<T>::contains_key(project_key);
+			ensure!(project_exists, Error::<T>::InvalidProjectIndexes);
+			ensure!(rules.is_valid(), Error::<T>::InvalidParam);
+			ProjectVotingRules::<T>::insert(project_key, rules.clone());
+			Self::deposit_event(Event::VotingRulesSet(Some(project_key), rules));
+
+			Ok(().into())
+		}
+
 		/// set is_identity_required
 		#[pallet::weight(<T as Config>::WeightInfo::set_is_identity_required())]
 		pub fn set_is_identity_required(origin: OriginFor<T>, is_identity_required: bool) -> DispatchResultWithPostInfo {
-			ensure_root(origin)?;
+			T::AdminOrigin::ensure_origin(origin)?;
 			IsIdentityRequired::<T>::put(is_identity_required);
 
 			Ok(().into())
 		}
+
+		/// Claim the caller's share of the unclaimed funds a project returned to its contributors
+		#[pallet::weight(<T as Config>::WeightInfo::claim_returned_funds())]
+		pub fn claim_returned_funds(origin: OriginFor<T>, project_key: ProjectIndex) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let project_exists = Projects::<T>::contains_key(project_key);
+			ensure!(project_exists, Error::<T>::InvalidProjectIndexes);
+
+			let contribution = Contributions::<T>::get(project_key, &who);
+			ensure!(contribution > (0 as u32).into(), Error::<T>::OnlyContributorsCanRefund);
+
+			let mut claim = Self::settle_returned_funds(project_key, &who);
+			let claimable = claim.unclaimed;
+			ensure!(claimable > (0 as u32).into(), Error::<T>::NothingToClaim);
+
+			Self::transfer_project_funds(project_key, &Self::project_account_id(project_key), &who, claimable)?;
+			claim.unclaimed = (0 as u32).into();
+			ReturnedFundsClaimed::<T>::insert(project_key, &who, claim);
+
+			Self::deposit_event(Event::ReturnedFundsClaimed(who, project_key, claimable));
+
+			Ok(().into())
+		}
+
+		/// Set where unclaimed funds go once a withdrawal window closes
+		#[pallet::weight(<T as Config>::WeightInfo::set_unclaimed_funds_policy())]
+		pub fn set_unclaimed_funds_policy(origin: OriginFor<T>, destination: UnclaimedFundsDestination) -> DispatchResultWithPostInfo {
+			T::AdminOrigin::ensure_origin(origin)?;
+			UnclaimedFundsPolicy::<T>::put(destination);
+			Self::deposit_event(Event::UnclaimedFundsPolicySet(destination));
+
+			Ok(().into())
+		}
 	}
 }
 
 impl<T: Config> Pallet<T> {
+	/// Check that an account has a reasonable or known good judgement, if identities are required
+	fn ensure_identity(who: &T::AccountId) -> DispatchResult {
+		if !IsIdentityRequired::<T>::get() {
+			return Ok(());
+		}
+
+		ensure!(Self::has_verified_identity(who), Error::<T>::IdentityNeeded);
+		Ok(())
+	}
+
+	/// Whether an account has an identity with a reasonable or known good judgement
+	pub fn has_verified_identity(who: &T::AccountId) -> bool {
+		match pallet_identity::Pallet::<T>::identity(who.clone()) {
+			Some(identity) => identity.judgements.iter().any(|judgement| {
+				judgement.1 == pallet_identity::Judgement::Reasonable || judgement.1 == pallet_identity::Judgement::KnownGood
+			}),
+			None => false,
+		}
+	}
+
+	/// Check that the caller owns a project that has not been scheduled in a round yet
+	fn ensure_editable(who: &T::AccountId, project_key: ProjectIndex) -> Result<ProjectOf<T>, DispatchError> {
+		let project_exists = Projects::<T>::contains_key(project_key);
+		ensure!(project_exists, Error::<T>::InvalidProjectIndexes);
+		let project = Projects::<T>::get(project_key);
+		ensure!(*who == project.owner, Error::<T>::InvalidAccount);
+		ensure!(project.state == ProjectState::Draft, Error::<T>::InvalidProjectState);
+		Ok(project)
+	}
+
+	/// The name of the scheduler task opening or closing a round
+	fn round_task_id(round_index: RoundIndex, is_start: bool) -> Vec<u8> {
+		(ROUND_TASK_ID, round_index, is_start).encode()
+	}
+
+	/// Have the scheduler dispatch `start_round` or `end_round` for a round at `when`
+	fn schedule_round_task(round_index: RoundIndex, when: T::BlockNumber, is_start: bool) -> DispatchResult {
+		let call = if is_start {
+			Call::<T>::start_round { round_index }
+		} else {
+			Call::<T>::end_round { round_index }
+		};
+		T::Scheduler::schedule_named(
+			Self::round_task_id(round_index, is_start),
+			DispatchTime::At(when),
+			None,
+			HARD_DEADLINE,
+			frame_system::RawOrigin::Root.into(),
+			call.into(),
+		).map_err(|_| Error::<T>::RoundNotScheduled)?;
+		Ok(())
+	}
+
+	/// Reverse the outcome of a disputed milestone, opening a withdrawal window if it is now approved
+	fn overturn_milestone(round_index: RoundIndex, project_key: ProjectIndex, milestone_index: MilestoneIndex) -> DispatchResult {
+		let mut project = Projects::<T>::get(project_key);
+		// Closed projects are refunding their contributors, so there is nothing left to overturn
+		if project.state.is_closed() {
+			return Ok(());
+		}
+		let mut proposal = <Proposals<T>>::get(round_index, project_key).ok_or(Error::<T>::NoActiveProposal)?;
+
+		let milestone = project.milestone_mut(milestone_index).ok_or(Error::<T>::MilestoneDoesNotExist)?;
+		milestone.is_approved = !milestone.is_approved;
+		let is_approved = milestone.is_approved;
+		<MilestoneVotes<T>>::mutate((project_key, milestone_index), |vote| vote.is_approved = is_approved);
+
+		let rules = Self::voting_rules(project_key);
+		if is_approved {
+			if !proposal.is_approved {
+				proposal.is_approved = true;
+				proposal.withdrawal_expiration = <frame_system::Pallet<T>>::block_number() + Self::withdrawal_window();
+				Self::schedule_withdrawal_expiry(round_index, project_key, proposal.withdrawal_expiration);
+			}
+			if project.state != ProjectState::InMilestones && Self::idle_state(&project) == ProjectState::InMilestones {
+				Self::set_state(project_key, &mut project, ProjectState::InMilestones)?;
+			}
+			Self::deposit_event(Event::MilestoneApproved(project_key, milestone_index, rules));
+		} else {
+			proposal.is_approved = project.milestones.iter()
+				.any(|milestone| milestone.is_approved && proposal.milestone_indexes.contains(&milestone.milestone_index));
+			let idle_state = Self::idle_state(&project);
+			if project.state == ProjectState::InMilestones && idle_state != ProjectState::InMilestones {
+				Self::set_state(project_key, &mut project, idle_state)?;
+			}
+			Self::deposit_event(Event::MilestoneRejected(project_key, milestone_index, rules));
+		}
+
+		<Projects<T>>::insert(project_key, project);
+		<Proposals<T>>::insert(round_index, project_key, proposal);
+		Ok(())
+	}
+
+	/// Build the milestones of a project, whose percentages must add up to 100
+	fn new_milestones(project_key: ProjectIndex, proposed_milestones: Vec<ProposedMilestone>) -> Result<BoundedMilestonesOf<T>, DispatchError> {
+		ensure!(proposed_milestones.len() as u32 <= T::MaxMilestonesPerProject::get(), Error::<T>::TooManyMilestones);
+		let mut total_percentage: u32 = 0;
+		for milestone in proposed_milestones.iter() {
+			ensure!(milestone.percentage_to_unlock > 0, Error::<T>::InvalidParam);
+			total_percentage = total_percentage.saturating_add(milestone.percentage_to_unlock);
+		}
+		ensure!(total_percentage == 100, Error::<T>::MilestonesTotalPercentageMustEqual100);
+
+		let mut milestones: BoundedMilestonesOf<T> = Default::default();
+		let mut milestone_index: u32 = 0;
+		for milestone in proposed_milestones {
+			milestones.try_push(Milestone {
+				project_key,
+				milestone_index,
+				name: Self::bound_string(milestone.name)?,
+				percentage_to_unlock: milestone.percentage_to_unlock,
+				is_approved: false,
+			}).map_err(|_| Error::<T>::TooManyMilestones)?;
+			milestone_index = milestone_index.checked_add(1).ok_or(Error::<T>::Overflow)?;
+		}
+		Ok(milestones)
+	}
+
+	/// Check a name, website or milestone name against `MaxStringLength`
+	fn bound_string(value: Vec<u8>) -> Result<BoundedStringOf<T>, DispatchError> {
+		value.try_into().map_err(|_| Error::<T>::ParamLimitExceed.into())
+	}
+
+	/// Check a logo or description, raw bytes against `MaxContentLength` and CIDs against `MAX_CID_LENGTH`
+	fn bound_content(content: ProjectContent<Vec<u8>>) -> Result<ProjectContentOf<T>, DispatchError> {
+		let content = match content {
+			ProjectContent::Raw(bytes) => {
+				ensure!(bytes.len() > 0, Error::<T>::InvalidParam);
+				ProjectContent::Raw(bytes.try_into().map_err(|_| Error::<T>::ParamLimitExceed)?)
+			},
+			ProjectContent::Ipfs(cid) => {
+				ensure!(cid.len() > 0, Error::<T>::InvalidParam);
+				ensure!(cid.len() <= MAX_CID_LENGTH as usize, Error::<T>::ParamLimitExceed);
+				ProjectContent::Ipfs(cid.try_into().map_err(|_| Error::<T>::ParamLimitExceed)?)
+			},
+			ProjectContent::Hash(hash) => ProjectContent::Hash(hash),
+		};
+		Ok(content)
+	}
+
 	/// The account ID of the fund pot.
 	///
 	/// This actually does computation. If you need to keep using it, then make sure you cache the
@@ -763,139 +1679,1290 @@ impl<T: Config> Pallet<T> {
 		T::PalletId::get().into_sub_account(index)
 	}
 
-	/// Get all projects
-	pub fn get_projects() -> Vec<Project<AccountIdOf<T>, BalanceOf<T>, T::BlockNumber>> {
-		let len = ProjectCount::<T>::get();
-		let mut projects: Vec<Project<AccountIdOf<T>, BalanceOf<T>, T::BlockNumber>> = Vec::new();
-		for i in 0..len {
-			let project = <Projects<T>>::get(i);
-			projects.push(project);
+	/// Finalise the rounds that have ended, carrying over what does not fit in `MaxFinalizationWeight`
+	/// Each round is charged for the proposals it holds, and at least one round is finalised every block.
+	fn finalize_ended_rounds(now: T::BlockNumber) -> Weight {
+		let ended_rounds = RoundEnds::<T>::take(now);
+		let mut queue = FinalizationQueue::<T>::get();
+		let mut weight = T::DbWeight::get().reads_writes(3, 1);
+
+		if ended_rounds.is_empty() && queue.is_empty() {
+			return weight;
+		}
+		queue.extend(ended_rounds);
+
+		let max_weight = T::MaxFinalizationWeight::get();
+		let mut finalized_count = 0;
+		for round_index in queue.iter() {
+			let proposal_count = RoundProjects::<T>::decode_len(round_index).unwrap_or_default() as u32;
+			let round_weight = <T as Config>::WeightInfo::finalize_round(proposal_count, T::MaxMilestonesPerProject::get())
+				.saturating_add(T::DbWeight::get().reads(1));
+			if finalized_count > 0 && weight.saturating_add(round_weight) > max_weight {
+				break;
+			}
+			Self::finalize_round(*round_index, now);
+			weight = weight.saturating_add(round_weight);
+			finalized_count += 1;
 		}
-		projects
-	}
 
-	pub fn get_project(project_key: u32) -> Project<AccountIdOf<T>, BalanceOf<T>, T::BlockNumber> {
-		let project = <Projects<T>>::get(project_key);
-		project
+		queue.drain(..finalized_count);
+		FinalizationQueue::<T>::put(queue);
+		weight.saturating_add(T::DbWeight::get().writes(1))
 	}
 
-}
+	/// Expire the withdrawal windows closing at `now` within what is left of `MaxFinalizationWeight`,
+	/// carrying the rest over to the next block
+	fn expire_withdrawals(now: T::BlockNumber, mut weight: Weight) -> Weight {
+		let expiring = WithdrawalExpiries::<T>::take(now);
+		weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
+		if expiring.is_empty() {
+			return weight;
+		}
 
-pub type RoundIndex = u32;
-pub type ProjectIndex = u32;
-pub type MilestoneIndex = u32;
+		let max_weight = T::MaxFinalizationWeight::get();
+		let expiry_weight = <T as Config>::WeightInfo::expire_withdrawal();
+		let mut expired_count = 0;
+		for (round_index, project_key) in expiring.iter() {
+			if weight.saturating_add(expiry_weight) > max_weight {
+				break;
+			}
+			Self::expire_withdrawal(*round_index, *project_key, now);
+			weight = weight.saturating_add(expiry_weight);
+			expired_count += 1;
+		}
 
+		if expired_count < expiring.len() {
+			let next_block = now.saturating_add(1u32.into());
+			WithdrawalExpiries::<T>::mutate(next_block, |next| next.extend_from_slice(&expiring[expired_count..]));
+			weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
+		}
+		weight
+	}
 
-type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
-type BalanceOf<T> = <<T as Config>::Currency as Currency<AccountIdOf<T>>>::Balance;
-type ContributionOf<T> = Contribution<AccountIdOf<T>, BalanceOf<T>>;
-type RoundOf<T> = Round<AccountIdOf<T>, BalanceOf<T>, <T as frame_system::Config>::BlockNumber>;
-type ProposalOf<T> = Proposal<AccountIdOf<T>, BalanceOf<T>, <T as frame_system::Config>::BlockNumber>;
+	/// Queue an approved proposal to be expired once its withdrawal window has closed
+	fn schedule_withdrawal_expiry(round_index: RoundIndex, project_key: ProjectIndex, withdrawal_expiration: T::BlockNumber) {
+		let expiry_block = withdrawal_expiration.saturating_add(1u32.into());
+		WithdrawalExpiries::<T>::append(expiry_block, (round_index, project_key));
+	}
 
-/// Round struct
-#[derive(Encode, Decode, Default, PartialEq, Eq, Clone, Debug, TypeInfo)]
-pub struct Round<AccountId, Balance, BlockNumber> {
-	start: BlockNumber,
-	end: BlockNumber,
-	proposals: Vec<Proposal<AccountId, Balance, BlockNumber>>,
-	is_canceled: bool,
-}
+	/// Give an approved proposal a new withdrawal window if its last one has closed
+	fn reopen_withdrawal_window(round_index: RoundIndex, project_key: ProjectIndex) {
+		let now = <frame_system::Pallet<T>>::block_number();
+		let mut proposal = match <Proposals<T>>::get(round_index, project_key) {
+			Some(proposal) if proposal.is_approved && !proposal.is_canceled && proposal.withdrawal_expiration < now => proposal,
+			_ => return,
+		};
+		proposal.withdrawal_expiration = now + Self::withdrawal_window();
+		Self::schedule_withdrawal_expiry(round_index, project_key, proposal.withdrawal_expiration);
+		<Proposals<T>>::insert(round_index, project_key, proposal);
+	}
 
-impl<AccountId, Balance: From<u32>, BlockNumber: From<u32>> Round<AccountId, Balance, BlockNumber> {
-		fn new(start: BlockNumber, end: BlockNumber, project_key: ProjectIndex, milestone_indexes: Vec<MilestoneIndex>) -> Round<AccountId, Balance, BlockNumber> { 
-			let mut proposal_round  = Round {
-				start: start,
-				end: end,
-				proposals: Vec::new(),
-				is_canceled: false,
-			};
+	/// Send what is still unclaimed of the milestones a proposal approved wherever `UnclaimedFundsPolicy` says
+	/// Stale entries, for proposals whose window was extended or that can no longer be withdrawn from, are ignored.
+	/// Milestones with a pending dispute are left until it is resolved, which opens a new window.
+	fn expire_withdrawal(round_index: RoundIndex, project_key: ProjectIndex, now: T::BlockNumber) {
+		let proposal = match <Proposals<T>>::get(round_index, project_key) {
+			Some(proposal) if proposal.is_approved && !proposal.is_canceled && proposal.withdrawal_expiration < now => proposal,
+			_ => return,
+		};
+		if !Projects::<T>::contains_key(project_key) {
+			return;
+		}
+		let project = Projects::<T>::get(project_key);
+		if project.state.is_closed() {
+			return;
+		}
 
-			// let project =  Self::get_project(project_key);
+		let total_funds = project.raised_funds.saturating_add(ProjectMatchingFunds::<T>::get(project_key));
+		let mut expired_milestones = Vec::new();
+		let mut unclaimed_funds: BalanceOf<T> = (0 as u32).into();
+		for milestone in project.milestones.iter() {
+			if !milestone.is_approved
+				|| !proposal.milestone_indexes.contains(&milestone.milestone_index)
+				|| ExpiredMilestones::<T>::contains_key(project_key, milestone.milestone_index)
+				|| Disputes::<T>::contains_key(project_key, milestone.milestone_index) {
+				continue;
+			}
+			let paid = MilestonePayouts::<T>::get(project_key, milestone.milestone_index)
+				.map(|payout| payout.amount)
+				.unwrap_or_else(|| (0 as u32).into());
+			let unclaimed = Self::milestone_share(total_funds, milestone).saturating_sub(paid);
+			expired_milestones.push((milestone.milestone_index, unclaimed));
+			unclaimed_funds = unclaimed_funds.saturating_add(unclaimed);
+		}
 
-			// let mut round_milestones = Vec::new();
+		let destination = match Self::release_unclaimed_funds(project_key, project.raised_funds, unclaimed_funds) {
+			Ok(destination) => destination,
+			Err(e) => {
+				log::warn!("Failed to send unclaimed funds of project {} to the treasury: {:?}", project_key, e);
+				return;
+			},
+		};
 
+		for (milestone_index, unclaimed) in expired_milestones {
+			ExpiredMilestones::<T>::insert(project_key, milestone_index, unclaimed);
+		}
+		Self::deposit_event(Event::UnclaimedFundsReturned(round_index, project_key, unclaimed_funds, destination));
 
+		Self::complete_if_settled(project_key);
+	}
 
-			// for milestone_index in milestone_indexes {
-			// 	for milestone in project.milestones {
-			// 		if milestone.milestone_index  == milestone_index {
-			// 			round_milestones.push(milestone)
-			// 		}
-			// 	}
-			// }
-				
+	/// Send the share of expired milestones in what a project raised since they expired wherever `UnclaimedFundsPolicy` says
+	/// The owner can no longer withdraw it, so it is released when the round the funds were raised in is finalised.
+	fn release_expired_shares(round_index: RoundIndex, project_key: ProjectIndex) {
+		if !Projects::<T>::contains_key(project_key) {
+			return;
+		}
+		let project = Projects::<T>::get(project_key);
+		if project.state.is_closed() {
+			return;
+		}
 
+		let total_funds = project.raised_funds.saturating_add(ProjectMatchingFunds::<T>::get(project_key));
+		let mut expired_milestones = Vec::new();
+		let mut unclaimed_funds: BalanceOf<T> = (0 as u32).into();
+		for (milestone_index, expired) in ExpiredMilestones::<T>::iter_prefix(project_key) {
+			let milestone = match project.milestones.iter().find(|milestone| milestone.milestone_index == milestone_index) {
+				Some(milestone) => milestone,
+				None => continue,
+			};
+			let paid = MilestonePayouts::<T>::get(project_key, milestone_index)
+				.map(|payout| payout.amount)
+				.unwrap_or_else(|| (0 as u32).into());
+			let unclaimed = Self::milestone_share(total_funds, milestone).saturating_sub(paid).saturating_sub(expired);
+			if unclaimed > (0 as u32).into() {
+				expired_milestones.push((milestone_index, expired.saturating_add(unclaimed)));
+				unclaimed_funds = unclaimed_funds.saturating_add(unclaimed);
+			}
+		}
+		if unclaimed_funds == (0 as u32).into() {
+			return;
+		}
 
-			proposal_round.proposals.push(Proposal {
-				project_key: project_key,
-				milestone_indexes: milestone_indexes,
-				contributions: Vec::new(),
-				is_approved: false,
-				is_canceled: false,
-				is_withdrawn: false,
-				withdrawal_expiration: (0 as u32).into(),
-			});
-			proposal_round
+		match Self::release_unclaimed_funds(project_key, project.raised_funds, unclaimed_funds) {
+			Ok(destination) => {
+				for (milestone_index, expired) in expired_milestones {
+					ExpiredMilestones::<T>::insert(project_key, milestone_index, expired);
+				}
+				Self::deposit_event(Event::UnclaimedFundsReturned(round_index, project_key, unclaimed_funds, destination));
+			},
+			Err(e) => log::warn!("Failed to send unclaimed funds of project {} to the treasury: {:?}", project_key, e),
+		}
+	}
+
+	/// Return unclaimed funds of a project to its contributors or send them to the treasury, as `UnclaimedFundsPolicy` says
+	/// The treasury only takes the native currency, assets are always returned to contributors.
+	fn release_unclaimed_funds(project_key: ProjectIndex, raised_funds: BalanceOf<T>, amount: BalanceOf<T>) -> Result<UnclaimedFundsDestination, DispatchError> {
+		let destination = match ProjectCurrencies::<T>::get(project_key) {
+			FundingCurrency::Native => UnclaimedFundsPolicy::<T>::get(),
+			FundingCurrency::Asset(_) => UnclaimedFundsDestination::Contributors,
+		};
+		if amount == (0 as u32).into() {
+			return Ok(destination);
+		}
+
+		match destination {
+			UnclaimedFundsDestination::Contributors => Self::return_funds(project_key, amount, raised_funds),
+			UnclaimedFundsDestination::Treasury => {
+				let imbalance = <T as Config>::Currency::withdraw(
+					&Self::project_account_id(project_key),
+					amount,
+					WithdrawReasons::from(WithdrawReasons::TRANSFER),
+					ExistenceRequirement::AllowDeath,
+				)?;
+				T::Treasury::on_unbalanced(imbalance);
+			},
+		}
+		Ok(destination)
+	}
+
+	/// Set funds aside for the current contributors of a project, with what they had raised between them
+	/// Batches returned while nobody contributed are shared by the same contributors, so they are merged.
+	fn return_funds(project_key: ProjectIndex, amount: BalanceOf<T>, raised_funds: BalanceOf<T>) {
+		ReturnedFunds::<T>::mutate(project_key, |batches| {
+			let batch = ReturnedFundsBatch { amount, raised_funds };
+			let is_merged = match batches.last_mut() {
+				Some(last) if last.raised_funds == raised_funds => {
+					last.amount = last.amount.saturating_add(amount);
+					true
+				},
+				_ => false,
+			};
+			// Every batch expires a milestone, so there is room for one batch per milestone
+			if !is_merged && batches.try_push(batch).is_err() {
+				log::warn!("Too many batches of returned funds for project {}, merging into the last one", project_key);
+				if let Some(last) = batches.last_mut() {
+					last.amount = last.amount.saturating_add(amount);
+				}
+			}
+		});
+	}
+
+	/// Credit a contributor with their share of the batches returned since they were last credited
+	/// Contributions only change after settling, so the current contribution is what they had when those batches were returned.
+	fn settle_returned_funds(project_key: ProjectIndex, who: &AccountIdOf<T>) -> ReturnedFundsClaimOf<T> {
+		let batches = ReturnedFunds::<T>::get(project_key);
+		let mut claim = ReturnedFundsClaimed::<T>::get(project_key, who);
+		if claim.settled_batches as usize >= batches.len() {
+			return claim;
+		}
+
+		let contribution = Contributions::<T>::get(project_key, who);
+		for batch in batches.iter().skip(claim.settled_batches as usize) {
+			let share = Perbill::from_rational(contribution, batch.raised_funds) * batch.amount;
+			claim.unclaimed = claim.unclaimed.saturating_add(share);
+		}
+		claim.settled_batches = batches.len() as u32;
+		ReturnedFundsClaimed::<T>::insert(project_key, who, claim.clone());
+		claim
+	}
+
+	/// The funds of a project that expired before the owner withdrew them
+	fn expired_funds(project_key: ProjectIndex) -> BalanceOf<T> {
+		ExpiredMilestones::<T>::iter_prefix_values(project_key)
+			.fold((0 as u32).into(), |expired: BalanceOf<T>, unclaimed| expired.saturating_add(unclaimed))
+	}
+
+	/// Check that `who` may vote on a milestone right now, returning their voting weight
+	fn ensure_can_vote(who: &AccountIdOf<T>, project_key: ProjectIndex, milestone_index: MilestoneIndex) -> Result<(BalanceOf<T>, MilestoneSubmissionOf<T>), DispatchError> {
+		let project_count = ProjectCount::<T>::get();
+		ensure!(project_key < project_count, Error::<T>::InvalidParam);
+		let now = <frame_system::Pallet<T>>::block_number();
+		
+		// round list must be not none
+		ensure!(RoundCount::<T>::get() > 0, Error::<T>::NoActiveRound);
+
+		let (processing_round_index, _) = Self::processing_round(now).ok_or(Error::<T>::RoundNotProcessing)?;
+
+		// Find proposal by index
+		let proposal = <Proposals<T>>::get(processing_round_index, project_key).ok_or(Error::<T>::NoActiveProposal)?;
+
+		let project_exists = Projects::<T>::contains_key(project_key.clone());
+		ensure!(project_exists, Error::<T>::InvalidProjectIndexes);
+
+		ensure!(!proposal.is_canceled, Error::<T>::ProposalCanceled);
+		ensure!(proposal.milestone_indexes.contains(&milestone_index), Error::<T>::MilestoneNotProposed);
+		ensure!(!Projects::<T>::get(project_key).state.is_closed(), Error::<T>::ProjectClosed);
+
+		// Weigh the whole contribution with the weighting of the current round and the voter's current identity
+		ensure!(Contributions::<T>::contains_key(project_key, who), Error::<T>::OnlyContributorsCanVote);
+		let weighting = RoundVoteWeightings::<T>::get(processing_round_index, project_key);
+		let vote_weight = T::VoteWeigher::vote_weight(&weighting, who, Contributions::<T>::get(project_key, who));
+
+		// Votes are only accepted while the milestone submission is open for voting
+		let submission = MilestoneSubmissions::<T>::get((project_key, milestone_index)).ok_or(Error::<T>::MilestoneNotSubmitted)?;
+		ensure!(now <= submission.voting_end, Error::<T>::MilestoneVotingClosed);
+
+		Ok((vote_weight, submission))
+	}
+
+	/// A contributor's vote on the current submission of a milestone
+	/// Votes cast on an earlier submission of the milestone no longer count and are ignored.
+	fn current_user_vote(who: &AccountIdOf<T>, project_key: ProjectIndex, milestone_index: MilestoneIndex, submitted_at: T::BlockNumber) -> Option<UserVote<BalanceOf<T>, T::BlockNumber>> {
+		UserVotes::<T>::get((who.clone(), project_key, milestone_index))
+			.filter(|user_vote| user_vote.submitted_at == submitted_at)
+	}
+
+	/// Update a contributor's voting weight in a project, and the project's total with it
+	fn set_vote_weight(project_key: ProjectIndex, who: &AccountIdOf<T>, weight: BalanceOf<T>) {
+		let previous_weight = VoteWeights::<T>::get(project_key, who);
+		VoteWeights::<T>::insert(project_key, who, weight);
+		TotalVoteWeights::<T>::mutate(project_key, |total| *total = total.saturating_sub(previous_weight).saturating_add(weight));
+	}
+
+	/// Record a contributor's vote on a milestone, weighted by their voting weight in the project
+	/// A previous vote is replaced, removing the weight it was cast with from the tally. The voter's weight is
+	/// refreshed, since the weighting or their identity may have changed since they contributed.
+	fn do_vote(who: AccountIdOf<T>, project_key: ProjectIndex, milestone_index: MilestoneIndex, vote_option: VoteOption) -> DispatchResult {
+		let (vote_weight, submission) = Self::ensure_can_vote(&who, project_key, milestone_index)?;
+		let now = <frame_system::Pallet<T>>::block_number();
+
+		if vote_option == VoteOption::Abstain {
+			ensure!(Self::voting_rules(project_key).allow_abstain, Error::<T>::AbstainNotAllowed);
+		}
+
+		let vote_lookup_key = (who.clone(), project_key, milestone_index);
+		let previous_vote = Self::current_user_vote(&who, project_key, milestone_index, submission.submitted_at);
+		if let Some(previous_vote) = &previous_vote {
+			ensure!(previous_vote.option != vote_option, Error::<T>::VoteAlreadyExists);
+		}
+
+		Self::set_vote_weight(project_key, &who, vote_weight);
+		let mut current_vote = <MilestoneVotes<T>>::get((project_key, milestone_index));
+		if let Some(previous_vote) = &previous_vote {
+			current_vote.remove(previous_vote);
+		}
+		let user_vote = UserVote {
+			option: vote_option,
+			weight: vote_weight,
+			submitted_at: submission.submitted_at,
+		};
+		current_vote.add(&user_vote);
+		<MilestoneVotes<T>>::insert((project_key, milestone_index), current_vote);
+		<UserVotes<T>>::insert(vote_lookup_key, user_vote);
+
+		if previous_vote.is_some() {
+			Self::deposit_event(Event::VoteChanged(who, project_key, milestone_index, vote_option, now));
+		} else {
+			match vote_option {
+				VoteOption::Abstain => Self::deposit_event(Event::VoteAbstained(who, project_key, milestone_index, now)),
+				_ => Self::deposit_event(Event::VoteComplete(who, project_key, milestone_index, vote_option == VoteOption::Yay, now)),
+			}
+		}
+
+		Ok(())
+	}
+
+	/// The voting rules of a project, falling back to the current default for projects created before rules existed
+	pub fn voting_rules(project_key: ProjectIndex) -> VotingRules {
+		ProjectVotingRules::<T>::get(project_key).unwrap_or_else(DefaultVotingRules::<T>::get)
+	}
+
+	/// How long approved funds can be withdrawn for, falling back to `MaxWithdrawalExpiration` when none was set
+	pub fn withdrawal_window() -> T::BlockNumber {
+		let withdrawal_expiration = <WithdrawalExpiration<T>>::get();
+		if withdrawal_expiration.is_zero() {
+			T::MaxWithdrawalExpiration::get()
+		} else {
+			withdrawal_expiration
+		}
+	}
+
+	/// Apply a project's voting rules to a milestone tally
+	/// Turnout, including abstentions when allowed, must reach `min_turnout` of the voting weight of all contributors,
+	/// and the yay share of the yay and nay votes must exceed `approval_threshold`.
+	fn is_vote_approved(vote: &Vote<BalanceOf<T>>, rules: &VotingRules, total_vote_weight: BalanceOf<T>) -> bool {
+		let decisive_votes = vote.yay.saturating_add(vote.nay);
+		let turnout = if rules.allow_abstain {
+			decisive_votes.saturating_add(vote.abstain)
+		} else {
+			decisive_votes
+		};
+
+		turnout >= rules.min_turnout * total_vote_weight
+			&& vote.yay > rules.approval_threshold * decisive_votes
+	}
+
+	/// Tally the milestone votes of every proposal in an ended round
+	fn finalize_round(round_index: RoundIndex, now: T::BlockNumber) {
+		let mut round = match <Rounds<T>>::get(round_index) {
+			Some(round) => round,
+			None => return,
+		};
+		if round.is_canceled || round.is_finalized {
+			return;
+		}
+
+		for project_key in RoundProjects::<T>::get(round_index) {
+			let mut proposal = match <Proposals<T>>::get(round_index, project_key) {
+				Some(proposal) => proposal,
+				None => continue,
+			};
+			if proposal.is_canceled || !Projects::<T>::contains_key(project_key) {
+				continue;
+			}
+			let mut project = Projects::<T>::get(project_key);
+
+			// Projects short of their soft cap are closed so contributors can be refunded
+			if project.state == ProjectState::Fundraising
+				&& project.raised_funds < ProjectSoftCaps::<T>::get(project_key)
+				&& !Self::is_in_open_round(project_key, round_index)
+			{
+				Self::deposit_event(Event::SoftCapMissed(round_index, project_key, project.raised_funds));
+				proposal.is_canceled = true;
+				<Proposals<T>>::insert(round_index, project_key, proposal);
+				ActiveRounds::<T>::remove(project_key, round_index);
+				match Self::close_project(project_key, &mut project) {
+					Ok(()) => Self::release_deposit(project_key),
+					Err(e) => log::warn!("Failed to close project {} short of its soft cap: {:?}", project_key, e),
+				}
+				continue;
+			}
+
+			let rules = Self::voting_rules(project_key);
+			let total_vote_weight = TotalVoteWeights::<T>::get(project_key);
+
+			for milestone_index in proposal.milestone_indexes.iter() {
+				// Milestones approved since the round was scheduled stay approved
+				let milestone = match project.milestone_mut(*milestone_index) {
+					Some(milestone) if !milestone.is_approved => milestone,
+					_ => continue,
+				};
+
+				// Only milestones submitted during the round, with voting closed, are tallied
+				let vote_key = (project_key, *milestone_index);
+				match MilestoneSubmissions::<T>::get(vote_key) {
+					Some(submission) if Self::is_submitted_in(&round, &submission) && submission.voting_end < now => (),
+					_ => continue,
+				}
+				let mut vote = <MilestoneVotes<T>>::get(vote_key);
+				vote.is_approved = Self::is_vote_approved(&vote, &rules, total_vote_weight);
+				<MilestoneVotes<T>>::insert(vote_key, vote.clone());
+
+				if vote.is_approved {
+					milestone.is_approved = true;
+					proposal.is_approved = true;
+					Self::deposit_event(Event::MilestoneApproved(project_key, *milestone_index, rules.clone()));
+				} else {
+					Self::deposit_event(Event::MilestoneRejected(project_key, *milestone_index, rules.clone()));
+				}
+			}
+
+			if proposal.is_approved {
+				proposal.withdrawal_expiration = now + Self::withdrawal_window();
+				Self::schedule_withdrawal_expiry(round_index, project_key, proposal.withdrawal_expiration);
+			}
+
+			// Projects still raising funds in a later round keep doing so until they have approved milestones
+			if !project.state.is_closed() {
+				let next_state = Self::idle_state(&project);
+				if next_state == ProjectState::InMilestones || !Self::is_in_open_round(project_key, round_index) {
+					if let Err(e) = Self::set_state(project_key, &mut project, next_state) {
+						log::warn!("Failed to update the state of project {} at the end of round {}: {:?}", project_key, round_index, e);
+					}
+				}
+			}
+			<Projects<T>>::insert(project_key, project);
+			<Proposals<T>>::insert(round_index, project_key, proposal);
+		}
+
+		let carried_over = UnallocatedMatchingFund::<T>::take();
+		if carried_over > (0 as u32).into() {
+			round.matching_fund = round.matching_fund.saturating_add(carried_over);
+			Self::deposit_event(Event::MatchingFundCarriedOver(round_index, carried_over));
+		}
+		let distributed = Self::distribute_matching_fund(round_index, &round);
+		Self::release_matching_fund(round.matching_fund.saturating_sub(distributed));
+
+		for project_key in RoundProjects::<T>::get(round_index) {
+			Self::release_expired_shares(round_index, project_key);
+			ActiveRounds::<T>::remove(project_key, round_index);
+		}
+		UpcomingRounds::<T>::mutate(|upcoming_rounds| upcoming_rounds.retain(|index| *index != round_index));
+		LastFinalizedRoundEnd::<T>::put(round.end);
+
+		round.is_finalized = true;
+		<Rounds<T>>::insert(round_index, Some(round));
+		Self::deposit_event(Event::RoundFinalized(round_index));
+	}
+
+	/// Whether a milestone submission was made while a round was open
+	fn is_submitted_in(round: &RoundOf<T>, submission: &MilestoneSubmissionOf<T>) -> bool {
+		round.start <= submission.submitted_at && submission.submitted_at < round.end
+	}
+
+	/// Split a round's matching pool between its proposals with the quadratic funding formula, returning what was paid out
+	/// Each proposal is weighted by the square of the sum of the square roots of its contributions.
+	fn distribute_matching_fund(round_index: RoundIndex, round: &RoundOf<T>) -> BalanceOf<T> {
+		let mut distributed: BalanceOf<T> = (0 as u32).into();
+		if round.matching_fund == (0 as u32).into() {
+			return distributed;
+		}
+
+		let mut total_weight: u128 = 0;
+		let mut proposal_weights = Vec::new();
+		for (project_key, proposal) in <Proposals<T>>::iter_prefix(round_index) {
+			// Matching funds are in the native currency, so only projects raising it are matched
+			if proposal.is_canceled || ProjectCurrencies::<T>::get(project_key) != FundingCurrency::Native {
+				continue;
+			}
+			let sqrt_sum = proposal.contribution_sqrt_sum;
+			let weight = sqrt_sum.saturating_mul(sqrt_sum);
+			total_weight = total_weight.saturating_add(weight);
+			proposal_weights.push((project_key, weight));
+		}
+
+		if total_weight == 0 {
+			return distributed;
+		}
+
+		for (project_key, weight) in proposal_weights {
+			let matching_fund = Perbill::from_rational(weight, total_weight) * round.matching_fund;
+			if matching_fund == (0 as u32).into() {
+				continue;
+			}
+
+			let transfer = <T as Config>::Currency::transfer(
+				&Self::account_id(),
+				&Self::project_account_id(project_key),
+				matching_fund,
+				ExistenceRequirement::AllowDeath
+			);
+			if let Err(e) = transfer {
+				log::warn!("Failed to pay matching fund of round {} to project {}: {:?}", round_index, project_key, e);
+				continue;
+			}
+
+			ProjectMatchingFunds::<T>::mutate(project_key, |funds| *funds = funds.saturating_add(matching_fund));
+			distributed = distributed.saturating_add(matching_fund);
+			Self::deposit_event(Event::MatchingFundDistributed(round_index, project_key, matching_fund));
+		}
+
+		distributed
+	}
+
+	/// Return matching funds held by the pallet to the unallocated pool
+	fn release_matching_fund(value: BalanceOf<T>) {
+		if value > (0 as u32).into() {
+			UnallocatedMatchingFund::<T>::mutate(|funds| *funds = funds.saturating_add(value));
+		}
+	}
+
+	/// Check that milestone indexes name distinct milestones of a project that have not been approved yet
+	fn ensure_open_milestones(project: &ProjectOf<T>, milestone_indexes: &[MilestoneIndex]) -> DispatchResult {
+		for (i, milestone_index) in milestone_indexes.iter().enumerate() {
+			ensure!(!milestone_indexes[..i].contains(milestone_index), Error::<T>::InvalidParam);
+			let milestone = project.milestones.iter()
+				.find(|milestone| milestone.milestone_index == *milestone_index)
+				.ok_or(Error::<T>::MilestoneDoesNotExist)?;
+			ensure!(!milestone.is_approved, Error::<T>::MilestoneApproved);
+		}
+
+		Ok(())
+	}
+
+	/// Check that a project that took part in a round is being refunded
+	fn ensure_refundable(round_index: RoundIndex, project_key: ProjectIndex) -> Result<ProjectOf<T>, DispatchError> {
+		let project_exists = Projects::<T>::contains_key(project_key);
+		ensure!(project_exists, Error::<T>::InvalidProjectIndexes);
+		let project = Projects::<T>::get(project_key);
+		ensure!(project.state == ProjectState::Refunding, Error::<T>::ProposalNotCanceled);
+		ensure!(<Proposals<T>>::contains_key(round_index, project_key), Error::<T>::NoActiveProposal);
+
+		Ok(project)
+	}
+
+	/// Close a project, so it takes no more contributions or withdrawals and its contributors can be refunded
+	fn close_project(project_key: ProjectIndex, project: &mut ProjectOf<T>) -> DispatchResult {
+		let next_state = if project.raised_funds > (0 as u32).into() {
+			ProjectState::Refunding
+		} else {
+			ProjectState::Cancelled
+		};
+		Self::set_state(project_key, project, next_state)?;
+		<Projects<T>>::insert(project_key, project.clone());
+		PendingOwners::<T>::remove(project_key);
+
+		// Unspent matching funds go back to the matching pot for a later round rather than to contributors
+		let matching_funds = ProjectMatchingFunds::<T>::get(project_key);
+		let unspent_matching_funds = Self::unspent_fraction(project_key, project) * matching_funds;
+		if unspent_matching_funds > (0 as u32).into() {
+			<T as Config>::Currency::transfer(
+				&Self::project_account_id(project_key),
+				&Self::account_id(),
+				unspent_matching_funds,
+				ExistenceRequirement::AllowDeath
+			)?;
+			Self::release_matching_fund(unspent_matching_funds);
+		}
+
+		Self::deposit_event(Event::ProjectClosed(project_key));
+		Ok(())
+	}
+
+	/// The deposit reserved for a project, by how much it stores on chain
+	fn project_deposit(project: &ProjectOf<T>) -> BalanceOf<T> {
+		let bytes: BalanceOf<T> = (project.encoded_size() as u32).into();
+		T::ProjectDepositBase::get().saturating_add(T::ProjectDepositPerByte::get().saturating_mul(bytes))
+	}
+
+	/// Reserve more or release part of a project's deposit after it changed size
+	fn adjust_deposit(project_key: ProjectIndex, project: &ProjectOf<T>) -> DispatchResult {
+		let (depositor, deposit) = match ProjectDeposits::<T>::get(project_key) {
+			Some(deposit) => deposit,
+			None => return Ok(()),
+		};
+
+		let new_deposit = Self::project_deposit(project);
+		if new_deposit > deposit {
+			let extra = new_deposit - deposit;
+			<T as Config>::Currency::reserve(&depositor, extra)?;
+			Self::deposit_event(Event::ProjectDepositReserved(project_key, depositor.clone(), extra));
+		} else if new_deposit < deposit {
+			let excess = deposit - new_deposit;
+			<T as Config>::Currency::unreserve(&depositor, excess);
+			Self::deposit_event(Event::ProjectDepositReleased(project_key, depositor.clone(), excess));
+		}
+		ProjectDeposits::<T>::insert(project_key, (depositor, new_deposit));
+		Ok(())
+	}
+
+	/// Return a project's deposit to the account it was reserved from
+	fn release_deposit(project_key: ProjectIndex) {
+		if let Some((depositor, deposit)) = ProjectDeposits::<T>::take(project_key) {
+			<T as Config>::Currency::unreserve(&depositor, deposit);
+			Self::deposit_event(Event::ProjectDepositReleased(project_key, depositor, deposit));
+		}
+	}
+
+	/// Complete a project once every milestone has been approved and either paid in full or expired,
+	/// releasing its deposit
+	fn complete_if_settled(project_key: ProjectIndex) {
+		let mut project = Projects::<T>::get(project_key);
+		if project.state != ProjectState::InMilestones {
+			return;
+		}
+		let total_funds = project.raised_funds.saturating_add(ProjectMatchingFunds::<T>::get(project_key));
+		let is_settled = project.milestones.iter().all(|milestone| {
+			let paid = MilestonePayouts::<T>::get(project_key, milestone.milestone_index)
+				.map(|payout| payout.amount)
+				.unwrap_or_else(|| (0 as u32).into());
+			milestone.is_approved && (
+				paid >= Self::milestone_share(total_funds, milestone)
+				|| ExpiredMilestones::<T>::contains_key(project_key, milestone.milestone_index)
+			)
+		});
+		if !is_settled {
+			return;
+		}
+
+		if Self::set_state(project_key, &mut project, ProjectState::Completed).is_ok() {
+			<Projects<T>>::insert(project_key, project);
+			Self::release_deposit(project_key);
+		}
+	}
+
+	/// Move a project to a new state, if its current state allows it
+	fn set_state(project_key: ProjectIndex, project: &mut ProjectOf<T>, next_state: ProjectState) -> DispatchResult {
+		if project.state == next_state {
+			return Ok(());
+		}
+		ensure!(project.state.can_become(&next_state), Error::<T>::InvalidProjectState);
+
+		let previous_state = sp_std::mem::replace(&mut project.state, next_state.clone());
+		Self::deposit_event(Event::ProjectStateChanged(project_key, previous_state, next_state));
+		Ok(())
+	}
+
+	/// The state of a project that is not raising funds in any round
+	fn idle_state(project: &ProjectOf<T>) -> ProjectState {
+		if project.milestones.iter().any(|milestone| milestone.is_approved) {
+			ProjectState::InMilestones
+		} else if project.raised_funds > (0 as u32).into() {
+			ProjectState::Funded
+		} else {
+			ProjectState::Draft
+		}
+	}
+
+	/// Whether a project takes part in a round, other than `except`, that has not been cancelled or finalised
+	fn is_in_open_round(project_key: ProjectIndex, except: RoundIndex) -> bool {
+		ActiveRounds::<T>::iter_prefix(project_key).any(|(round_index, _)| round_index != except)
+	}
+
+	/// The round open for contributions and votes at `now`, from its start block up to its end block
+	/// Upcoming rounds start in order and never overlap, so the search stops at the first one yet to start.
+	fn processing_round(now: T::BlockNumber) -> Option<(RoundIndex, RoundOf<T>)> {
+		for round_index in UpcomingRounds::<T>::get() {
+			let round = match <Rounds<T>>::get(round_index) {
+				Some(round) => round,
+				None => continue,
+			};
+			if round.start > now {
+				break;
+			}
+			if round.end > now {
+				return Some((round_index, round));
+			}
+		}
+		None
+	}
+
+	/// The part of a project's funds a milestone unlocks once approved
+	fn milestone_share<S>(total_funds: BalanceOf<T>, milestone: &Milestone<S>) -> BalanceOf<T> {
+		(total_funds * milestone.percentage_to_unlock.into()) / 100u32.into()
+	}
+
+	/// The share of a project's funds, contributions and matching funds alike, that the owner has not withdrawn
+	fn unspent_fraction(project_key: ProjectIndex, project: &ProjectOf<T>) -> Perbill {
+		let total_funds = project.raised_funds.saturating_add(ProjectMatchingFunds::<T>::get(project_key));
+		// Expired funds have either left the project or are set aside for `claim_returned_funds`
+		let remaining_funds = total_funds
+			.saturating_sub(project.withdrawn_funds)
+			.saturating_sub(Self::expired_funds(project_key));
+		Perbill::from_rational(remaining_funds, total_funds)
+	}
+
+	/// Move funds in the currency a project raises its funds in
+	fn transfer_project_funds(project_key: ProjectIndex, from: &AccountIdOf<T>, to: &AccountIdOf<T>, amount: BalanceOf<T>) -> DispatchResult {
+		match ProjectCurrencies::<T>::get(project_key) {
+			FundingCurrency::Native => <T as Config>::Currency::transfer(from, to, amount, ExistenceRequirement::AllowDeath),
+			FundingCurrency::Asset(asset_id) => {
+				// Amounts are kept in the native balance type, which may be wider than the asset's
+				let amount: u128 = amount.try_into().map_err(|_| Error::<T>::Overflow)?;
+				let amount: AssetBalanceOf<T> = amount.try_into().map_err(|_| Error::<T>::Overflow)?;
+				<T::Assets as fungibles::Transfer<AccountIdOf<T>>>::transfer(asset_id, from, to, amount, false)?;
+				Ok(())
+			},
+		}
+	}
+
+	/// Return the unspent part of a contribution from the project account
+	fn do_refund(project_key: ProjectIndex, project: &ProjectOf<T>, who: AccountIdOf<T>, contribution_value: BalanceOf<T>) -> DispatchResult {
+		let refund_amount = Self::unspent_fraction(project_key, project) * contribution_value;
+
+		Self::transfer_project_funds(project_key, &Self::project_account_id(project_key), &who, refund_amount)?;
+
+		Refunds::<T>::insert((project_key, who.clone()), refund_amount);
+		Self::deposit_event(Event::ContributorRefunded(who, project_key, refund_amount));
+
+		Ok(())
+	}
+
+	/// Get all projects
+	pub fn get_projects() -> Vec<Project<AccountIdOf<T>, BalanceOf<T>, T::BlockNumber>> {
+		let len = ProjectCount::<T>::get();
+		let mut projects: Vec<Project<AccountIdOf<T>, BalanceOf<T>, T::BlockNumber>> = Vec::new();
+		for i in 0..len {
+			let project = <Projects<T>>::get(i);
+			projects.push(project.into_unbounded());
+		}
+		projects
+	}
+
+	pub fn get_project(project_key: u32) -> Project<AccountIdOf<T>, BalanceOf<T>, T::BlockNumber> {
+		let project = <Projects<T>>::get(project_key);
+		project.into_unbounded()
+	}
+
+	/// Get a project, or `None` if it was never created
+	pub fn try_get_project(project_key: ProjectIndex) -> Option<Project<AccountIdOf<T>, BalanceOf<T>, T::BlockNumber>> {
+		if Projects::<T>::contains_key(project_key) {
+			Some(<Projects<T>>::get(project_key).into_unbounded())
+		} else {
+			None
+		}
+	}
+
+	/// Get the projects in a state
+	pub fn get_projects_by_state(state: ProjectState) -> Vec<ProjectIndex> {
+		let mut project_keys: Vec<ProjectIndex> = Projects::<T>::iter()
+			.filter(|(_, project)| project.state == state)
+			.map(|(project_key, _)| project_key)
+			.collect();
+		project_keys.sort();
+		project_keys
+	}
+
+	/// Get the round that is currently open for contributions and votes
+	pub fn get_active_round() -> Option<(RoundIndex, RoundOf<T>)> {
+		Self::processing_round(<frame_system::Pallet<T>>::block_number())
+	}
+
+	/// Get the projects taking part in a round
+	pub fn get_round_projects(round_index: RoundIndex) -> Vec<ProjectIndex> {
+		RoundProjects::<T>::get(round_index).into_inner()
+	}
+
+	/// Get the current yay/nay tally of every milestone of a project
+	pub fn get_milestone_votes(project_key: ProjectIndex) -> Vec<(MilestoneIndex, Vote<BalanceOf<T>>)> {
+		let project = <Projects<T>>::get(project_key);
+		project.milestones.iter()
+			.filter(|milestone| MilestoneVotes::<T>::contains_key((project_key, milestone.milestone_index)))
+			.map(|milestone| (milestone.milestone_index, MilestoneVotes::<T>::get((project_key, milestone.milestone_index))))
+			.collect()
+	}
+
+	/// Get what the owner has been paid for each milestone of a project
+	pub fn get_milestone_payouts(project_key: ProjectIndex) -> Vec<(MilestoneIndex, MilestonePayoutOf<T>)> {
+		let mut payouts: Vec<_> = MilestonePayouts::<T>::iter_prefix(project_key).collect();
+		payouts.sort_by_key(|(milestone_index, _)| *milestone_index);
+		payouts
+	}
+
+	/// Get the funds of a project, contributions and matching funds alike, that no approved milestone has unlocked yet
+	pub fn get_locked_funds(project_key: ProjectIndex) -> BalanceOf<T> {
+		let project = <Projects<T>>::get(project_key);
+		if project.state.is_closed() {
+			return (0 as u32).into();
+		}
+
+		let total_funds = project.raised_funds.saturating_add(ProjectMatchingFunds::<T>::get(project_key));
+		let unlocked_funds = project.milestones.iter()
+			.filter(|milestone| milestone.is_approved)
+			.fold((0 as u32).into(), |unlocked: BalanceOf<T>, milestone| unlocked.saturating_add(Self::milestone_share(total_funds, milestone)));
+		total_funds.saturating_sub(unlocked_funds)
+	}
+
+	/// Get the share of a contributor's funds that approved milestones have unlocked,
+	/// and how much of that the project owner has not withdrawn yet
+	pub fn get_contributor_funds(project_key: ProjectIndex, who: AccountIdOf<T>) -> ContributorFunds<BalanceOf<T>> {
+		let project = <Projects<T>>::get(project_key);
+
+		let total_contribution_amount = project.raised_funds;
+		let contributed = Contributions::<T>::get(project_key, &who);
+
+		let mut unlocked_percentage: u32 = 0;
+		for milestone in project.milestones.iter() {
+			if milestone.is_approved {
+				unlocked_percentage = unlocked_percentage.saturating_add(milestone.percentage_to_unlock);
+			}
+		}
+
+		let unlocked = Perbill::from_percent(unlocked_percentage.min(100)) * contributed;
+		let total_funds = total_contribution_amount.saturating_add(ProjectMatchingFunds::<T>::get(project_key));
+		let project_unlocked = Perbill::from_percent(unlocked_percentage.min(100)) * total_funds;
+		let project_withdrawable = project_unlocked.saturating_sub(project.withdrawn_funds);
+		let withdrawable = if total_contribution_amount > (0 as u32).into() {
+			Perbill::from_rational(contributed, total_contribution_amount) * project_withdrawable
+		} else {
+			(0 as u32).into()
+		};
+
+		ContributorFunds {
+			contributed,
+			unlocked,
+			withdrawable,
+		}
 	}
 }
+
+pub type RoundIndex = u32;
+pub type ProjectIndex = u32;
+pub type MilestoneIndex = u32;
+
+
+type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
+type BalanceOf<T> = <<T as Config>::Currency as Currency<AccountIdOf<T>>>::Balance;
+type BoundedStringOf<T> = BoundedVec<u8, <T as Config>::MaxStringLength>;
+type BoundedContentOf<T> = BoundedVec<u8, <T as Config>::MaxContentLength>;
+type ProjectContentOf<T> = ProjectContent<BoundedContentOf<T>>;
+type BoundedMilestonesOf<T> = BoundedVec<Milestone<BoundedStringOf<T>>, <T as Config>::MaxMilestonesPerProject>;
+type BoundedMilestoneIndexesOf<T> = BoundedVec<MilestoneIndex, <T as Config>::MaxMilestonesPerProject>;
+type ProjectOf<T> = Project<AccountIdOf<T>, BalanceOf<T>, <T as frame_system::Config>::BlockNumber, BoundedStringOf<T>, BoundedContentOf<T>, BoundedMilestonesOf<T>>;
+type RoundOf<T> = Round<BalanceOf<T>, <T as frame_system::Config>::BlockNumber>;
+type ProposalOf<T> = Proposal<<T as frame_system::Config>::BlockNumber, BoundedMilestoneIndexesOf<T>>;
+type MilestoneSubmissionOf<T> = MilestoneSubmission<<T as frame_system::Config>::BlockNumber, BoundedStringOf<T>>;
+type AssetBalanceOf<T> = <<T as Config>::Assets as fungibles::Inspect<AccountIdOf<T>>>::Balance;
+type FundingCurrencyOf<T> = FundingCurrency<<T as Config>::AssetId>;
+type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<AccountIdOf<T>>>::NegativeImbalance;
+type MilestonePayoutOf<T> = MilestonePayout<BalanceOf<T>, <T as frame_system::Config>::BlockNumber>;
+type DisputeOf<T> = Dispute<AccountIdOf<T>, BalanceOf<T>>;
+type VoteWeightingOf<T> = VoteWeighting<BalanceOf<T>>;
+type ReturnedFundsBatchOf<T> = ReturnedFundsBatch<BalanceOf<T>>;
+type ReturnedFundsClaimOf<T> = ReturnedFundsClaim<BalanceOf<T>>;
+
+/// Round struct
+/// The projects taking part in a round and their proposals are kept in `RoundProjects` and `Proposals`.
+#[derive(Encode, Decode, Default, PartialEq, Eq, Clone, Debug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct Round<Balance, BlockNumber> {
+	start: BlockNumber,
+	end: BlockNumber,
+	is_canceled: bool,
+	is_finalized: bool,
+	/// Funds to be split between the round's proposals with quadratic funding
+	matching_fund: Balance,
+}
+
+impl<Balance: From<u32>, BlockNumber> Round<Balance, BlockNumber> {
+	fn new(start: BlockNumber, end: BlockNumber) -> Round<Balance, BlockNumber> {
+		Round {
+			start: start,
+			end: end,
+			is_canceled: false,
+			is_finalized: false,
+			matching_fund: (0 as u32).into(),
+		}
+	}
+}
+
 // Proposal in round
 #[derive(Encode, Decode, Default, PartialEq, Eq, Clone, Debug, TypeInfo)]
-pub struct Proposal<AccountId, Balance, BlockNumber> {
-	project_key: ProjectIndex,
-	milestone_indexes: Vec<MilestoneIndex>,
-	contributions: Vec<Contribution<AccountId, Balance>>,
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct Proposal<BlockNumber, MilestoneIndexes = Vec<MilestoneIndex>> {
+	milestone_indexes: MilestoneIndexes,
+	/// The sum of the square roots of what each account contributed during the round
+	contribution_sqrt_sum: u128,
 	is_approved: bool,
 	is_canceled: bool,
 	is_withdrawn: bool,
 	withdrawal_expiration: BlockNumber,
 }
 
-/// The contribution users made to a proposal project.
-#[derive(Encode, Decode, Default, PartialEq, Eq, Clone, Debug, TypeInfo)]
-pub struct Contribution<AccountId, Balance> {
-	account_id: AccountId,
-	value: Balance,
+impl<BlockNumber: From<u32>, MilestoneIndexes> Proposal<BlockNumber, MilestoneIndexes> {
+	fn new(milestone_indexes: MilestoneIndexes) -> Proposal<BlockNumber, MilestoneIndexes> {
+		Proposal {
+			milestone_indexes: milestone_indexes,
+			contribution_sqrt_sum: 0,
+			is_approved: false,
+			is_canceled: false,
+			is_withdrawn: false,
+			withdrawal_expiration: (0 as u32).into(),
+		}
+	}
+}
+
+impl<BlockNumber, MilestoneIndexes> Proposal<BlockNumber, MilestoneIndexes> {
+	/// Update the quadratic funding sum when an account's contribution for the round changes
+	fn replace_contribution<Balance: UniqueSaturatedInto<u128>>(&mut self, previous: Balance, current: Balance) {
+		let previous: u128 = previous.saturated_into();
+		let current: u128 = current.saturated_into();
+		self.contribution_sqrt_sum = self.contribution_sqrt_sum
+			.saturating_sub(previous.integer_sqrt())
+			.saturating_add(current.integer_sqrt());
+	}
 }
 
 /// The contribution users made to a proposal project.
 #[derive(Encode, Decode, Default, PartialEq, Eq, Clone, Debug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub struct ProposedMilestone {
 	name: Vec<u8>,
 	percentage_to_unlock: u32,
 }
 
 /// The contribution users made to a proposal project.
+/// Names are bounded in storage and plain bytes as returned by the runtime API.
 #[derive(Encode, Decode, Default, PartialEq, Eq, Clone, Debug, TypeInfo)]
-pub struct Milestone {
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct Milestone<BoundedString = Vec<u8>> {
 	project_key: ProjectIndex,
 	milestone_index: MilestoneIndex,
-	name: Vec<u8>,
+	name: BoundedString,
 	percentage_to_unlock: u32,
 	is_approved: bool
 }
 
+impl<S> Milestone<BoundedVec<u8, S>> {
+	fn into_unbounded(self) -> Milestone {
+		Milestone {
+			project_key: self.project_key,
+			milestone_index: self.milestone_index,
+			name: self.name.into_inner(),
+			percentage_to_unlock: self.percentage_to_unlock,
+			is_approved: self.is_approved,
+		}
+	}
+}
+
+/// A project's logo or description, either stored on chain or referenced off chain.
+#[derive(Encode, Decode, PartialEq, Eq, Clone, Debug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum ProjectContent<Bytes> {
+	/// The content itself
+	Raw(Bytes),
+	/// The CID of the content on IPFS
+	Ipfs(Bytes),
+	/// The hash of content stored elsewhere
+	Hash(H256),
+}
+
+impl<Bytes: Default> Default for ProjectContent<Bytes> {
+	fn default() -> Self {
+		ProjectContent::Raw(Default::default())
+	}
+}
+
+impl<S> ProjectContent<BoundedVec<u8, S>> {
+	fn into_unbounded(self) -> ProjectContent<Vec<u8>> {
+		match self {
+			ProjectContent::Raw(bytes) => ProjectContent::Raw(bytes.into_inner()),
+			ProjectContent::Ipfs(cid) => ProjectContent::Ipfs(cid.into_inner()),
+			ProjectContent::Hash(hash) => ProjectContent::Hash(hash),
+		}
+	}
+}
+
+/// A milestone outcome awaiting `DisputeOrigin`, and the bond its raiser stands to lose.
+#[derive(Encode, Decode, Default, PartialEq, Eq, Clone, Debug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct Dispute<AccountId, Balance> {
+	pub round_index: RoundIndex,
+	pub raised_by: AccountId,
+	pub bond: Balance,
+}
+
+/// What has been paid to the project owner for a milestone, and when it was last paid.
+#[derive(Encode, Decode, Default, PartialEq, Eq, Clone, Debug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct MilestonePayout<Balance, BlockNumber> {
+	pub amount: Balance,
+	pub paid_at: BlockNumber,
+}
+
+/// Where a project is in its lifecycle.
+#[derive(Encode, Decode, PartialEq, Eq, Clone, Debug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum ProjectState {
+	/// Created, and not yet scheduled in a round
+	Draft,
+	/// Taking part in a round that has not been finalised
+	Fundraising,
+	/// Raised funds, with no milestone approved yet
+	Funded,
+	/// At least one milestone has been approved
+	InMilestones,
+	/// Every milestone has been approved and paid out or expired
+	Completed,
+	/// Closed before any funds were raised
+	Cancelled,
+	/// Closed, contributors can be refunded what the owner has not withdrawn
+	Refunding,
+}
+
+impl ProjectState {
+	/// Whether a project in this state can move to `next`
+	pub fn can_become(&self, next: &ProjectState) -> bool {
+		use ProjectState::*;
+		match (self, next) {
+			(Draft, Fundraising) | (Draft, Cancelled) => true,
+			(Fundraising, Draft) | (Fundraising, Funded) | (Fundraising, InMilestones)
+				| (Fundraising, Cancelled) | (Fundraising, Refunding) => true,
+			(Funded, Fundraising) | (Funded, InMilestones) | (Funded, Refunding) => true,
+			// An upheld dispute can take back the only approved milestone
+			(InMilestones, Funded) | (InMilestones, Completed) | (InMilestones, Refunding) => true,
+			_ => false,
+		}
+	}
+
+	/// Whether the project takes no more contributions, votes or withdrawals
+	pub fn is_closed(&self) -> bool {
+		matches!(self, ProjectState::Completed | ProjectState::Cancelled | ProjectState::Refunding)
+	}
+}
+
+impl Default for ProjectState {
+	fn default() -> Self {
+		ProjectState::Draft
+	}
+}
+
+/// The currency a project raises its funds in.
+#[derive(Encode, Decode, PartialEq, Eq, Clone, Copy, Debug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum FundingCurrency<AssetId> {
+	/// `Config::Currency`
+	Native,
+	/// An asset of `Config::Assets`
+	Asset(AssetId),
+}
+
+impl<AssetId> Default for FundingCurrency<AssetId> {
+	fn default() -> Self {
+		FundingCurrency::Native
+	}
+}
+
+/// Where the unclaimed funds of an approved milestone go once its withdrawal window closes.
+#[derive(Encode, Decode, PartialEq, Eq, Clone, Copy, Debug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum UnclaimedFundsDestination {
+	/// Set aside for contributors to claim pro rata
+	Contributors,
+	/// Sent to `Config::Treasury`
+	Treasury,
+}
+
+impl Default for UnclaimedFundsDestination {
+	fn default() -> Self {
+		UnclaimedFundsDestination::Contributors
+	}
+}
+
+/// Unclaimed funds returned to the contributors of a project at once
+#[derive(Encode, Decode, Default, PartialEq, Eq, Clone, Debug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct ReturnedFundsBatch<Balance> {
+	amount: Balance,
+	/// What the project had raised when the batch was returned, the contributions it is shared by
+	raised_funds: Balance,
+}
+
+/// A contributor's share of the funds returned by a project
+#[derive(Encode, Decode, Default, PartialEq, Eq, Clone, Debug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct ReturnedFundsClaim<Balance> {
+	/// How many of the project's batches the contributor has been credited with
+	settled_batches: u32,
+	unclaimed: Balance,
+}
+
+/// How a contributor voted on a milestone.
+#[derive(Encode, Decode, PartialEq, Eq, Clone, Copy, Debug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum VoteOption {
+	Nay,
+	Yay,
+	Abstain,
+}
+
+impl Default for VoteOption {
+	fn default() -> Self {
+		VoteOption::Nay
+	}
+}
+
+/// A contributor's vote, with the weight it was cast with so it can be changed or retracted.
+/// Votes only count towards the milestone submission made at `submitted_at`.
+#[derive(Encode, Decode, Default, PartialEq, Eq, Clone, Debug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct UserVote<Balance, BlockNumber> {
+	option: VoteOption,
+	weight: Balance,
+	submitted_at: BlockNumber,
+}
+
+/// The rules a milestone vote is tallied with.
+#[derive(Encode, Decode, Default, PartialEq, Eq, Clone, Debug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct VotingRules {
+	/// Minimum share of the project's contributions that must take part in the vote
+	pub min_turnout: Perbill,
+	/// Share of the yay and nay votes that must be exceeded by yay votes
+	pub approval_threshold: Perbill,
+	/// Whether contributors may abstain, counting towards turnout only
+	pub allow_abstain: bool,
+}
+
+impl VotingRules {
+	/// Whether a milestone could ever be approved under these rules
+	/// Yay votes must exceed the threshold, so a threshold of 100% could never be met.
+	pub fn is_valid(&self) -> bool {
+		self.approval_threshold < Perbill::one() && self.min_turnout <= Perbill::one()
+	}
+}
+
+/// How a contributor's contribution to a project is turned into voting weight.
+#[derive(Encode, Decode, PartialEq, Eq, Clone, Copy, Debug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum VoteWeighting<Balance> {
+	/// The contribution itself
+	Linear,
+	/// The square root of the contribution, so large contributors cannot outvote many small ones
+	SquareRoot,
+	/// The contribution, up to a cap per account
+	Capped(Balance),
+	/// The contribution, increased by a share of it for accounts with a verified identity
+	IdentityBonus(Perbill),
+}
+
+impl<Balance> Default for VoteWeighting<Balance> {
+	fn default() -> Self {
+		VoteWeighting::Linear
+	}
+}
+
+/// Turns what an account contributed to a project into voting weight.
+pub trait VoteWeigher<AccountId, Balance> {
+	fn vote_weight(weighting: &VoteWeighting<Balance>, who: &AccountId, contribution: Balance) -> Balance;
+}
+
+/// Weighs votes as each `VoteWeighting` describes, with identities judged through `pallet_identity`.
+pub struct StandardVoteWeigher<T>(sp_std::marker::PhantomData<T>);
+
+impl<T: Config> VoteWeigher<AccountIdOf<T>, BalanceOf<T>> for StandardVoteWeigher<T> {
+	fn vote_weight(weighting: &VoteWeightingOf<T>, who: &AccountIdOf<T>, contribution: BalanceOf<T>) -> BalanceOf<T> {
+		match weighting {
+			VoteWeighting::Linear => contribution,
+			VoteWeighting::SquareRoot => {
+				let contribution: u128 = contribution.saturated_into();
+				contribution.integer_sqrt().saturated_into()
+			},
+			VoteWeighting::Capped(cap) => contribution.min(*cap),
+			VoteWeighting::IdentityBonus(bonus) => {
+				if Pallet::<T>::has_verified_identity(who) {
+					contribution.saturating_add(*bonus * contribution)
+				} else {
+					contribution
+				}
+			},
+		}
+	}
+}
+
+/// Evidence submitted by the project owner that a milestone has been completed.
+/// The evidence is bounded by `MaxStringLength` in storage, which encodes the same as the unbounded
+/// bytes stored before, so existing submissions decode as they are.
+#[derive(Encode, Decode, Default, PartialEq, Eq, Clone, Debug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct MilestoneSubmission<BlockNumber, BoundedString = Vec<u8>> {
+	evidence: BoundedString,
+	submitted_at: BlockNumber,
+	voting_end: BlockNumber,
+}
+
 /// The contribution users made to a proposal project.
 #[derive(Encode, Decode, Default, PartialEq, Eq, Clone, Debug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub struct Vote<Balance> {
 	yay: Balance,
 	nay: Balance,
+	abstain: Balance,
 	is_approved: bool
 }
 
+impl<Balance: Saturating + Copy> Vote<Balance> {
+	fn add<BlockNumber>(&mut self, user_vote: &UserVote<Balance, BlockNumber>) {
+		match user_vote.option {
+			VoteOption::Yay => self.yay = self.yay.saturating_add(user_vote.weight),
+			VoteOption::Nay => self.nay = self.nay.saturating_add(user_vote.weight),
+			VoteOption::Abstain => self.abstain = self.abstain.saturating_add(user_vote.weight),
+		}
+	}
+
+	fn remove<BlockNumber>(&mut self, user_vote: &UserVote<Balance, BlockNumber>) {
+		match user_vote.option {
+			VoteOption::Yay => self.yay = self.yay.saturating_sub(user_vote.weight),
+			VoteOption::Nay => self.nay = self.nay.saturating_sub(user_vote.weight),
+			VoteOption::Abstain => self.abstain = self.abstain.saturating_sub(user_vote.weight),
+		}
+	}
+}
+
+/// A contributor's position in a project, as returned by the runtime API.
+#[derive(Encode, Decode, Default, PartialEq, Eq, Clone, Debug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct ContributorFunds<Balance> {
+	pub contributed: Balance,
+	pub unlocked: Balance,
+	pub withdrawable: Balance,
+}
+
 /// Project struct
+/// Text fields are bounded by `MaxStringLength` and `MaxContentLength` in storage, and plain bytes as returned by
+/// the runtime API.
 #[derive(Encode, Decode, Default, PartialEq, Eq, Clone, Debug, TypeInfo)]
-pub struct Project<AccountId, Balance, BlockNumber> {
-	name: Vec<u8>,
-	logo: Vec<u8>,
-	description: Vec<u8>,
-	website: Vec<u8>,
-	milestones: Vec<Milestone>,
-	contributions: Vec<Contribution<AccountId, Balance>>,
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct Project<AccountId, Balance, BlockNumber, BoundedString = Vec<u8>, BoundedContent = Vec<u8>, Milestones = Vec<Milestone<BoundedString>>> {
+	name: BoundedString,
+	logo: ProjectContent<BoundedContent>,
+	description: ProjectContent<BoundedContent>,
+	website: BoundedString,
+	milestones: Milestones,
+	/// The sum of all contributions, each contributor's total is kept in `Contributions`
+	raised_funds: Balance,
 	required_funds: Balance,
 	withdrawn_funds: Balance,
 	/// The account that will receive the funds if the campaign is successful
 	owner: AccountId,
 	create_block_number: BlockNumber,
+	/// Where the project is in its lifecycle
+	state: ProjectState,
+}
+
+impl<AccountId, Balance, BlockNumber, S, C, M> Project<AccountId, Balance, BlockNumber, BoundedVec<u8, S>, BoundedVec<u8, C>, BoundedVec<Milestone<BoundedVec<u8, S>>, M>> {
+	/// The milestone with the given index
+	fn milestone_mut(&mut self, milestone_index: MilestoneIndex) -> Option<&mut Milestone<BoundedVec<u8, S>>> {
+		let position = self.milestones.iter().position(|milestone| milestone.milestone_index == milestone_index)?;
+		self.milestones.get_mut(position)
+	}
+
+	fn into_unbounded(self) -> Project<AccountId, Balance, BlockNumber> {
+		Project {
+			name: self.name.into_inner(),
+			logo: self.logo.into_unbounded(),
+			description: self.description.into_unbounded(),
+			website: self.website.into_inner(),
+			milestones: self.milestones.into_iter().map(|milestone| milestone.into_unbounded()).collect(),
+			raised_funds: self.raised_funds,
+			required_funds: self.required_funds,
+			withdrawn_funds: self.withdrawn_funds,
+			owner: self.owner,
+			create_block_number: self.create_block_number,
+			state: self.state,
+		}
+	}
 }
 
 #[cfg(feature = "std")]
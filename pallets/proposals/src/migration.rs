@@ -0,0 +1,656 @@
+//! Storage migrations for the proposals pallet.
+
+use super::*;
+use frame_support::storage::unhashed;
+
+/// The layout of `Projects` in storage versions 1 and 2, before text fields were bounded
+#[derive(Encode, Decode, Clone)]
+struct ProjectV2<AccountId, Balance, BlockNumber> {
+	name: Vec<u8>,
+	logo: Vec<u8>,
+	description: Vec<u8>,
+	website: Vec<u8>,
+	milestones: Vec<Milestone>,
+	raised_funds: Balance,
+	required_funds: Balance,
+	withdrawn_funds: Balance,
+	owner: AccountId,
+	create_block_number: BlockNumber,
+	is_closed: bool,
+}
+
+type ProjectV2Of<T> = ProjectV2<AccountIdOf<T>, BalanceOf<T>, <T as frame_system::Config>::BlockNumber>;
+
+/// The layout of `Projects` in storage version 3, before `is_closed` became a `ProjectState`
+#[derive(Encode, Decode, Clone)]
+struct ProjectV3<AccountId, Balance, BlockNumber, BoundedString, BoundedContent> {
+	name: BoundedString,
+	logo: ProjectContent<BoundedContent>,
+	description: ProjectContent<BoundedContent>,
+	website: BoundedString,
+	milestones: Vec<Milestone<BoundedString>>,
+	raised_funds: Balance,
+	required_funds: Balance,
+	withdrawn_funds: Balance,
+	owner: AccountId,
+	create_block_number: BlockNumber,
+	is_closed: bool,
+}
+
+type ProjectV3Of<T> = ProjectV3<AccountIdOf<T>, BalanceOf<T>, <T as frame_system::Config>::BlockNumber, BoundedStringOf<T>, BoundedContentOf<T>>;
+
+/// Bounds the milestones of a project, or the milestone indexes of one of its proposals, by
+/// `MaxMilestonesPerProject`, dropping the ones that do not fit
+fn bound_milestones<T: Config, Item>(project_key: ProjectIndex, mut items: Vec<Item>) -> BoundedVec<Item, T::MaxMilestonesPerProject> {
+	let max_milestones = T::MaxMilestonesPerProject::get() as usize;
+	if items.len() > max_milestones {
+		log::warn!("Dropping {} milestones of project {}: too many milestones", items.len() - max_milestones, project_key);
+		items.truncate(max_milestones);
+	}
+	items.try_into().unwrap_or_default()
+}
+
+pub mod v1 {
+	//! Migrates from the layout the pallet was first deployed with.
+	//! Moves proposals out of `Rounds` into `RoundProjects` and `Proposals`, and contributions out of
+	//! proposals and projects into `RoundContributions` and `Contributions`. Also brings the layouts of
+	//! projects, rounds, milestone tallies and votes up to what `v2` expects:
+	//! - projects gain `raised_funds` and `is_closed`, as none could be closed yet
+	//! - rounds gain `is_finalized` and `matching_fund`. Rounds that ended before the upgrade were settled
+	//!   by `approve`, so they count as finalised, and the others are queued for finalisation at their end.
+	//! - milestone tallies gain `abstain`
+	//! - votes gain the option and weight they were cast with. They were cast before milestones were
+	//!   submitted, so they do not count towards any submission.
+
+	use super::*;
+	use frame_support::traits::GetStorageVersion;
+
+	#[derive(Encode, Decode, Clone)]
+	struct OldContribution<AccountId, Balance> {
+		account_id: AccountId,
+		value: Balance,
+	}
+
+	#[derive(Encode, Decode, Clone)]
+	struct OldProposal<AccountId, Balance, BlockNumber> {
+		project_key: ProjectIndex,
+		milestone_indexes: Vec<MilestoneIndex>,
+		contributions: Vec<OldContribution<AccountId, Balance>>,
+		is_approved: bool,
+		is_canceled: bool,
+		is_withdrawn: bool,
+		withdrawal_expiration: BlockNumber,
+	}
+
+	#[derive(Encode, Decode, Clone)]
+	struct OldRound<AccountId, Balance, BlockNumber> {
+		start: BlockNumber,
+		end: BlockNumber,
+		proposals: Vec<OldProposal<AccountId, Balance, BlockNumber>>,
+		is_canceled: bool,
+	}
+
+	#[derive(Encode, Decode, Clone)]
+	struct OldProject<AccountId, Balance, BlockNumber> {
+		name: Vec<u8>,
+		logo: Vec<u8>,
+		description: Vec<u8>,
+		website: Vec<u8>,
+		milestones: Vec<Milestone>,
+		contributions: Vec<OldContribution<AccountId, Balance>>,
+		required_funds: Balance,
+		withdrawn_funds: Balance,
+		owner: AccountId,
+		create_block_number: BlockNumber,
+	}
+
+	#[derive(Encode, Decode, Clone)]
+	struct OldVote<Balance> {
+		yay: Balance,
+		nay: Balance,
+		is_approved: bool,
+	}
+
+	type OldRoundOf<T> = OldRound<AccountIdOf<T>, BalanceOf<T>, <T as frame_system::Config>::BlockNumber>;
+	type OldProjectOf<T> = OldProject<AccountIdOf<T>, BalanceOf<T>, <T as frame_system::Config>::BlockNumber>;
+
+	pub fn migrate<T: Config>() -> Weight {
+		let onchain_version = Pallet::<T>::on_chain_storage_version();
+		if onchain_version >= 1 {
+			return T::DbWeight::get().reads(1);
+		}
+
+		let mut reads: Weight = 1;
+		let mut writes: Weight = 1;
+		let now = <frame_system::Pallet<T>>::block_number();
+
+		// Written in the v2 layout, which `v3` bounds
+		let project_keys: Vec<ProjectIndex> = Projects::<T>::iter_keys().collect();
+		for project_key in project_keys {
+			reads += 1;
+			let key = Projects::<T>::hashed_key_for(project_key);
+			let old = match unhashed::get::<OldProjectOf<T>>(&key) {
+				Some(old) => old,
+				None => {
+					log::warn!("Project {} could not be decoded and was not migrated", project_key);
+					continue;
+				}
+			};
+
+			let mut raised_funds: BalanceOf<T> = (0 as u32).into();
+			for contribution in old.contributions {
+				Contributions::<T>::mutate(project_key, &contribution.account_id, |value| *value = value.saturating_add(contribution.value));
+				raised_funds = raised_funds.saturating_add(contribution.value);
+				reads += 1;
+				writes += 1;
+			}
+
+			unhashed::put(&key, &ProjectV2 {
+				name: old.name,
+				logo: old.logo,
+				description: old.description,
+				website: old.website,
+				milestones: old.milestones,
+				raised_funds,
+				required_funds: old.required_funds,
+				withdrawn_funds: old.withdrawn_funds,
+				owner: old.owner,
+				create_block_number: old.create_block_number,
+				is_closed: false,
+			});
+			writes += 1;
+		}
+
+		Rounds::<T>::translate::<Option<OldRoundOf<T>>, _>(|round_index, old| {
+			reads += 1;
+			writes += 1;
+
+			let old = match old {
+				Some(old) => old,
+				None => return Some(None),
+			};
+
+			let mut project_keys: BoundedVec<ProjectIndex, T::MaxProposalsPerRound> = Default::default();
+			for old_proposal in old.proposals {
+				let project_key = old_proposal.project_key;
+				if project_keys.try_push(project_key).is_err() {
+					log::warn!("Dropping proposal of project {} from round {}: too many proposals", project_key, round_index);
+					continue;
+				}
+
+				let mut proposal = ProposalOf::<T>::new(bound_milestones::<T, _>(project_key, old_proposal.milestone_indexes));
+				proposal.is_approved = old_proposal.is_approved;
+				proposal.is_canceled = old_proposal.is_canceled;
+				proposal.is_withdrawn = old_proposal.is_withdrawn;
+				proposal.withdrawal_expiration = old_proposal.withdrawal_expiration;
+
+				for contribution in old_proposal.contributions {
+					let key = (round_index, project_key);
+					let previous = RoundContributions::<T>::get(key, &contribution.account_id);
+					let current = previous.saturating_add(contribution.value);
+					proposal.replace_contribution(previous, current);
+					RoundContributions::<T>::insert(key, &contribution.account_id, current);
+					reads += 1;
+					writes += 1;
+				}
+
+				<Proposals<T>>::insert(round_index, project_key, proposal);
+				writes += 1;
+			}
+			<RoundProjects<T>>::insert(round_index, project_keys);
+			writes += 1;
+
+			// Rounds still running are finalised at their end and announced by the scheduler like new ones
+			let is_finalized = old.end < now;
+			if !is_finalized && !old.is_canceled {
+				RoundEnds::<T>::append(old.end, round_index);
+				writes += 1;
+				if old.start > now && Pallet::<T>::schedule_round_task(round_index, old.start, true).is_err() {
+					log::warn!("Failed to schedule the start of round {}", round_index);
+				}
+				if old.end > now && Pallet::<T>::schedule_round_task(round_index, old.end, false).is_err() {
+					log::warn!("Failed to schedule the end of round {}", round_index);
+				}
+			}
+
+			Some(Some(Round {
+				start: old.start,
+				end: old.end,
+				is_canceled: old.is_canceled,
+				is_finalized,
+				matching_fund: (0 as u32).into(),
+			}))
+		});
+
+		MilestoneVotes::<T>::translate::<OldVote<BalanceOf<T>>, _>(|_, old| {
+			reads += 1;
+			writes += 1;
+			Some(Vote {
+				yay: old.yay,
+				nay: old.nay,
+				abstain: (0 as u32).into(),
+				is_approved: old.is_approved,
+			})
+		});
+
+		// Votes were weighted by the voter's contribution to the project
+		UserVotes::<T>::translate::<bool, _>(|(who, project_key, _), approve_milestone| {
+			reads += 2;
+			writes += 1;
+			Some(UserVote {
+				option: if approve_milestone { VoteOption::Yay } else { VoteOption::Nay },
+				weight: Contributions::<T>::get(project_key, &who),
+				submitted_at: Default::default(),
+			})
+		});
+
+		StorageVersion::new(1).put::<Pallet<T>>();
+		log::info!("Migrated proposals pallet storage to v1");
+
+		T::DbWeight::get().reads_writes(reads, writes)
+	}
+}
+
+pub mod v2 {
+	//! Seeds the `MilestonePayouts` ledger from each project's `withdrawn_funds`, so milestones
+	//! that were paid before the ledger existed are not paid again.
+
+	use super::*;
+	use frame_support::traits::GetStorageVersion;
+
+	pub fn migrate<T: Config>() -> Weight {
+		let onchain_version = Pallet::<T>::on_chain_storage_version();
+		if onchain_version != 1 {
+			return T::DbWeight::get().reads(1);
+		}
+
+		let mut reads: Weight = 1;
+		let mut writes: Weight = 1;
+		let now = <frame_system::Pallet<T>>::block_number();
+
+		let project_keys: Vec<ProjectIndex> = Projects::<T>::iter_keys().collect();
+		for project_key in project_keys {
+			reads += 2;
+			let project = match unhashed::get::<ProjectV2Of<T>>(&Projects::<T>::hashed_key_for(project_key)) {
+				Some(project) => project,
+				None => continue,
+			};
+			let total_funds = project.raised_funds.saturating_add(ProjectMatchingFunds::<T>::get(project_key));
+
+			// Attribute what has been withdrawn to the approved milestones in order
+			let mut unattributed = project.withdrawn_funds;
+			for milestone in project.milestones.iter().filter(|milestone| milestone.is_approved) {
+				if unattributed == (0 as u32).into() {
+					break;
+				}
+				let amount = Pallet::<T>::milestone_share(total_funds, milestone).min(unattributed);
+				unattributed = unattributed.saturating_sub(amount);
+				MilestonePayouts::<T>::insert(project_key, milestone.milestone_index, MilestonePayout { amount, paid_at: now });
+				writes += 1;
+			}
+		}
+
+		StorageVersion::new(2).put::<Pallet<T>>();
+		log::info!("Migrated proposals pallet storage to v2");
+
+		T::DbWeight::get().reads_writes(reads, writes)
+	}
+}
+
+pub mod v3 {
+	//! Bounds the text fields of projects by `MaxStringLength` and `MaxContentLength`.
+	//! Names and websites that are too long are truncated. Logos and descriptions that are too long
+	//! are replaced by the hash of their content, which the owner is expected to host off chain.
+
+	use super::*;
+	use frame_support::traits::GetStorageVersion;
+	use sp_io::hashing::blake2_256;
+
+	fn truncate<T: Config>(project_key: ProjectIndex, mut value: Vec<u8>) -> BoundedStringOf<T> {
+		let max_length = T::MaxStringLength::get() as usize;
+		if value.len() > max_length {
+			log::warn!("Truncating a text field of project {} to {} bytes", project_key, max_length);
+			value.truncate(max_length);
+		}
+		value.try_into().unwrap_or_default()
+	}
+
+	fn bound_content<T: Config>(project_key: ProjectIndex, content: Vec<u8>) -> ProjectContentOf<T> {
+		if content.len() > T::MaxContentLength::get() as usize {
+			log::warn!("Replacing content of project {} with its hash", project_key);
+			return ProjectContent::Hash(H256::from(blake2_256(&content)));
+		}
+		ProjectContent::Raw(content.try_into().unwrap_or_default())
+	}
+
+	pub fn migrate<T: Config>() -> Weight {
+		let onchain_version = Pallet::<T>::on_chain_storage_version();
+		if onchain_version != 2 {
+			return T::DbWeight::get().reads(1);
+		}
+
+		let mut reads: Weight = 1;
+		let mut writes: Weight = 1;
+
+		// Written in the v3 layout, which `v4` gives a state
+		let project_keys: Vec<ProjectIndex> = Projects::<T>::iter_keys().collect();
+		for project_key in project_keys {
+			reads += 1;
+			let key = Projects::<T>::hashed_key_for(project_key);
+			let old = match unhashed::get::<ProjectV2Of<T>>(&key) {
+				Some(old) => old,
+				None => continue,
+			};
+
+			let milestones = old.milestones.into_iter()
+				.map(|milestone| Milestone {
+					project_key: milestone.project_key,
+					milestone_index: milestone.milestone_index,
+					name: truncate::<T>(project_key, milestone.name),
+					percentage_to_unlock: milestone.percentage_to_unlock,
+					is_approved: milestone.is_approved,
+				})
+				.collect();
+
+			unhashed::put(&key, &ProjectV3 {
+				name: truncate::<T>(project_key, old.name),
+				logo: bound_content::<T>(project_key, old.logo),
+				description: bound_content::<T>(project_key, old.description),
+				website: truncate::<T>(project_key, old.website),
+				milestones,
+				raised_funds: old.raised_funds,
+				required_funds: old.required_funds,
+				withdrawn_funds: old.withdrawn_funds,
+				owner: old.owner,
+				create_block_number: old.create_block_number,
+				is_closed: old.is_closed,
+			});
+			writes += 1;
+		}
+
+		StorageVersion::new(3).put::<Pallet<T>>();
+		log::info!("Migrated proposals pallet storage to v3");
+
+		T::DbWeight::get().reads_writes(reads, writes)
+	}
+}
+
+pub mod v4 {
+	//! Replaces the `is_closed` flag of projects with a `ProjectState`. Closed projects were being refunded,
+	//! open ones are derived from their rounds, approved milestones and raised funds.
+
+	use super::*;
+	use frame_support::traits::GetStorageVersion;
+
+	/// Whether a project has a proposal in a round that has not been cancelled or finalised,
+	/// found by visiting the rounds since `ActiveRounds` is only seeded by `v5`
+	fn is_in_open_round<T: Config>(project_key: ProjectIndex) -> bool {
+		for round_index in (0..RoundCount::<T>::get()).rev() {
+			let round = match <Rounds<T>>::get(round_index) {
+				Some(round) => round,
+				None => continue,
+			};
+			if round.is_finalized {
+				break;
+			}
+			if round.is_canceled {
+				continue;
+			}
+			if let Some(proposal) = <Proposals<T>>::get(round_index, project_key) {
+				if !proposal.is_canceled {
+					return true;
+				}
+			}
+		}
+		false
+	}
+
+	pub fn migrate<T: Config>() -> Weight {
+		let onchain_version = Pallet::<T>::on_chain_storage_version();
+		if onchain_version != 3 {
+			return T::DbWeight::get().reads(1);
+		}
+
+		let mut reads: Weight = 1;
+		let mut writes: Weight = 1;
+		let round_count = RoundCount::<T>::get();
+		let mut in_milestones: Vec<ProjectIndex> = Vec::new();
+
+		Projects::<T>::translate::<ProjectV3Of<T>, _>(|project_key, old| {
+			reads += 1;
+			writes += 1;
+
+			let mut project = Project {
+				name: old.name,
+				logo: old.logo,
+				description: old.description,
+				website: old.website,
+				milestones: bound_milestones::<T, _>(project_key, old.milestones),
+				raised_funds: old.raised_funds,
+				required_funds: old.required_funds,
+				withdrawn_funds: old.withdrawn_funds,
+				owner: old.owner,
+				create_block_number: old.create_block_number,
+				state: ProjectState::Draft,
+			};
+
+			project.state = if old.is_closed {
+				ProjectState::Refunding
+			} else {
+				let idle_state = Pallet::<T>::idle_state(&project);
+				reads += round_count as Weight;
+				if idle_state != ProjectState::InMilestones && is_in_open_round::<T>(project_key) {
+					ProjectState::Fundraising
+				} else {
+					idle_state
+				}
+			};
+			if project.state == ProjectState::InMilestones {
+				in_milestones.push(project_key);
+			}
+
+			Some(project)
+		});
+
+		// Projects whose milestones were all paid out before they had a state
+		for project_key in in_milestones {
+			Pallet::<T>::complete_if_settled(project_key);
+			reads += 4;
+			writes += 2;
+		}
+
+		StorageVersion::new(4).put::<Pallet<T>>();
+		log::info!("Migrated proposals pallet storage to v4");
+
+		T::DbWeight::get().reads_writes(reads, writes)
+	}
+}
+
+pub mod v5 {
+	//! Seeds the `UpcomingRounds` queue, the `ActiveRounds` index and `LastFinalizedRoundEnd` from the
+	//! existing rounds, so the open round is found without visiting every round.
+
+	use super::*;
+	use frame_support::traits::GetStorageVersion;
+
+	pub fn migrate<T: Config>() -> Weight {
+		let onchain_version = Pallet::<T>::on_chain_storage_version();
+		if onchain_version != 4 {
+			return T::DbWeight::get().reads(1);
+		}
+
+		let mut reads: Weight = 2;
+		let mut writes: Weight = 3;
+		let mut upcoming_rounds: BoundedVec<RoundIndex, T::MaxUpcomingRounds> = Default::default();
+		let mut last_finalized_round_end: Option<T::BlockNumber> = None;
+
+		// Rounds start after the previous one ended, so their indexes are in start order
+		for round_index in 0..RoundCount::<T>::get() {
+			reads += 1;
+			let round = match <Rounds<T>>::get(round_index) {
+				Some(round) => round,
+				None => continue,
+			};
+			if round.is_canceled {
+				continue;
+			}
+			if round.is_finalized {
+				last_finalized_round_end = Some(round.end);
+				continue;
+			}
+
+			if upcoming_rounds.try_push(round_index).is_err() {
+				log::warn!("Round {} is not indexed: more than MaxUpcomingRounds rounds are upcoming", round_index);
+				continue;
+			}
+			for (project_key, proposal) in <Proposals<T>>::iter_prefix(round_index) {
+				reads += 1;
+				if !proposal.is_canceled {
+					ActiveRounds::<T>::insert(project_key, round_index, ());
+					writes += 1;
+				}
+			}
+		}
+
+		UpcomingRounds::<T>::put(upcoming_rounds);
+		if let Some(end) = last_finalized_round_end {
+			LastFinalizedRoundEnd::<T>::put(end);
+		}
+
+		StorageVersion::new(5).put::<Pallet<T>>();
+		log::info!("Migrated proposals pallet storage to v5");
+
+		T::DbWeight::get().reads_writes(reads, writes)
+	}
+}
+
+pub mod v6 {
+	//! Gives every existing contributor a voting weight equal to their contribution, as under
+	//! `VoteWeighting::Linear`, and totals them per project.
+
+	use super::*;
+	use frame_support::traits::GetStorageVersion;
+
+	pub fn migrate<T: Config>() -> Weight {
+		let onchain_version = Pallet::<T>::on_chain_storage_version();
+		if onchain_version != 5 {
+			return T::DbWeight::get().reads(1);
+		}
+
+		let mut reads: Weight = 1;
+		let mut writes: Weight = 1;
+
+		for (project_key, who, contribution) in Contributions::<T>::iter() {
+			VoteWeights::<T>::insert(project_key, &who, contribution);
+			TotalVoteWeights::<T>::mutate(project_key, |total| *total = total.saturating_add(contribution));
+			reads += 2;
+			writes += 2;
+		}
+
+		StorageVersion::new(6).put::<Pallet<T>>();
+		log::info!("Migrated proposals pallet storage to v6");
+
+		T::DbWeight::get().reads_writes(reads, writes)
+	}
+}
+
+pub mod v7 {
+	//! Bounds the milestones of projects and the milestone indexes of proposals by `MaxMilestonesPerProject`.
+	//! Their encoding is unchanged, so only lists longer than the bound lose the milestones that do not fit.
+
+	use super::*;
+	use frame_support::traits::GetStorageVersion;
+
+	type ProjectV6Of<T> = Project<AccountIdOf<T>, BalanceOf<T>, <T as frame_system::Config>::BlockNumber, BoundedStringOf<T>, BoundedContentOf<T>>;
+
+	pub fn migrate<T: Config>() -> Weight {
+		let onchain_version = Pallet::<T>::on_chain_storage_version();
+		if onchain_version != 6 {
+			return T::DbWeight::get().reads(1);
+		}
+
+		let mut reads: Weight = 1;
+		let mut writes: Weight = 1;
+
+		Projects::<T>::translate::<ProjectV6Of<T>, _>(|project_key, old| {
+			reads += 1;
+			writes += 1;
+			Some(Project {
+				name: old.name,
+				logo: old.logo,
+				description: old.description,
+				website: old.website,
+				milestones: bound_milestones::<T, _>(project_key, old.milestones),
+				raised_funds: old.raised_funds,
+				required_funds: old.required_funds,
+				withdrawn_funds: old.withdrawn_funds,
+				owner: old.owner,
+				create_block_number: old.create_block_number,
+				state: old.state,
+			})
+		});
+
+		Proposals::<T>::translate::<Proposal<T::BlockNumber>, _>(|_round_index, project_key, old| {
+			reads += 1;
+			writes += 1;
+			let mut proposal = ProposalOf::<T>::new(bound_milestones::<T, _>(project_key, old.milestone_indexes));
+			proposal.contribution_sqrt_sum = old.contribution_sqrt_sum;
+			proposal.is_approved = old.is_approved;
+			proposal.is_canceled = old.is_canceled;
+			proposal.is_withdrawn = old.is_withdrawn;
+			proposal.withdrawal_expiration = old.withdrawal_expiration;
+			Some(proposal)
+		});
+
+		StorageVersion::new(7).put::<Pallet<T>>();
+		log::info!("Migrated proposals pallet storage to v7");
+
+		T::DbWeight::get().reads_writes(reads, writes)
+	}
+}
+
+pub mod v8 {
+	//! Splits the funds returned to contributors of a project into batches, each shared by those who had
+	//! contributed when it was returned. What was returned before is one batch, shared by the current contributors.
+
+	use super::*;
+	use frame_support::traits::GetStorageVersion;
+
+	pub fn migrate<T: Config>() -> Weight {
+		let onchain_version = Pallet::<T>::on_chain_storage_version();
+		if onchain_version != 7 {
+			return T::DbWeight::get().reads(1);
+		}
+
+		let mut reads: Weight = 1;
+		let mut writes: Weight = 1;
+
+		// Claims are translated first, while the totals they were taken from are still readable
+		ReturnedFundsClaimed::<T>::translate::<BalanceOf<T>, _>(|project_key, who, claimed| {
+			reads += 4;
+			writes += 1;
+			let returned_funds = unhashed::get::<BalanceOf<T>>(&ReturnedFunds::<T>::hashed_key_for(project_key)).unwrap_or_default();
+			let raised_funds = Projects::<T>::get(project_key).raised_funds;
+			let share = Perbill::from_rational(Contributions::<T>::get(project_key, &who), raised_funds) * returned_funds;
+			Some(ReturnedFundsClaim {
+				settled_batches: 1,
+				unclaimed: share.saturating_sub(claimed),
+			})
+		});
+
+		ReturnedFunds::<T>::translate::<BalanceOf<T>, _>(|project_key, returned_funds| {
+			reads += 2;
+			writes += 1;
+			let batch = ReturnedFundsBatch {
+				amount: returned_funds,
+				raised_funds: Projects::<T>::get(project_key).raised_funds,
+			};
+			Some(vec![batch].try_into().unwrap_or_default())
+		});
+
+		StorageVersion::new(8).put::<Pallet<T>>();
+		log::info!("Migrated proposals pallet storage to v8");
+
+		T::DbWeight::get().reads_writes(reads, writes)
+	}
+}
@@ -0,0 +1,259 @@
+use crate as proposals;
+use frame_support::{
+	ord_parameter_types, parameter_types,
+	traits::{EqualPrivilegeOnly, Everything, GenesisBuild, Hooks},
+	weights::Weight,
+	PalletId,
+};
+use frame_system::{EnsureOneOf, EnsureRoot, EnsureSignedBy};
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+	Perbill,
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+pub type AccountId = u64;
+pub type Balance = u64;
+pub type BlockNumber = u64;
+pub type AssetId = u32;
+
+pub const ALICE: AccountId = 1;
+pub const BOB: AccountId = 2;
+pub const CHARLIE: AccountId = 3;
+pub const DAVE: AccountId = 4;
+pub const REGISTRAR: AccountId = 10;
+pub const APPROVER: AccountId = 11;
+
+pub const USDT: AssetId = 1;
+
+pub const INITIAL_BALANCE: Balance = 1_000_000;
+
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Identity: pallet_identity::{Pallet, Call, Storage, Event<T>},
+		Assets: pallet_assets::{Pallet, Call, Storage, Event<T>},
+		Scheduler: pallet_scheduler::{Pallet, Call, Storage, Event<T>},
+		ImbueProposals: proposals::{Pallet, Call, Storage, Event<T>},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const SS58Prefix: u8 = 42;
+}
+
+impl frame_system::Config for Test {
+	type BaseCallFilter = Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = BlockNumber;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<Balance>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = SS58Prefix;
+	type OnSetCode = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: Balance = 1;
+	pub const MaxLocks: u32 = 50;
+	pub const MaxReserves: u32 = 50;
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = MaxLocks;
+	type MaxReserves = MaxReserves;
+	type ReserveIdentifier = [u8; 8];
+	type Balance = Balance;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const BasicDeposit: Balance = 10;
+	pub const FieldDeposit: Balance = 1;
+	pub const SubAccountDeposit: Balance = 1;
+	pub const MaxSubAccounts: u32 = 2;
+	pub const MaxAdditionalFields: u32 = 2;
+	pub const MaxRegistrars: u32 = 2;
+}
+
+impl pallet_identity::Config for Test {
+	type Event = Event;
+	type Currency = Balances;
+	type BasicDeposit = BasicDeposit;
+	type FieldDeposit = FieldDeposit;
+	type SubAccountDeposit = SubAccountDeposit;
+	type MaxSubAccounts = MaxSubAccounts;
+	type MaxAdditionalFields = MaxAdditionalFields;
+	type MaxRegistrars = MaxRegistrars;
+	type Slashed = ();
+	type ForceOrigin = EnsureRoot<AccountId>;
+	type RegistrarOrigin = EnsureRoot<AccountId>;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const AssetDeposit: Balance = 1;
+	pub const MetadataDepositBase: Balance = 1;
+	pub const MetadataDepositPerByte: Balance = 1;
+	pub const ApprovalDeposit: Balance = 1;
+	pub const AssetsStringLimit: u32 = 50;
+}
+
+impl pallet_assets::Config for Test {
+	type Event = Event;
+	type Balance = Balance;
+	type AssetId = AssetId;
+	type Currency = Balances;
+	type ForceOrigin = EnsureRoot<AccountId>;
+	type AssetDeposit = AssetDeposit;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type ApprovalDeposit = ApprovalDeposit;
+	type StringLimit = AssetsStringLimit;
+	type Freezer = ();
+	type Extra = ();
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const MaximumSchedulerWeight: Weight = 1_000_000_000;
+	pub const MaxScheduledPerBlock: u32 = 50;
+}
+
+impl pallet_scheduler::Config for Test {
+	type Event = Event;
+	type Origin = Origin;
+	type PalletsOrigin = OriginCaller;
+	type Call = Call;
+	type MaximumWeight = MaximumSchedulerWeight;
+	type ScheduleOrigin = EnsureRoot<AccountId>;
+	type MaxScheduledPerBlock = MaxScheduledPerBlock;
+	type WeightInfo = ();
+	type OriginPrivilegeCmp = EqualPrivilegeOnly;
+}
+
+parameter_types! {
+	pub const ProposalsPalletId: PalletId = PalletId(*b"imbgrant");
+	pub const MaxProposalsPerRound: u32 = 10;
+	pub const MaxMilestonesPerProject: u32 = 10;
+	pub const MaxUpcomingRounds: u32 = 4;
+	pub const MaxWithdrawalExpiration: BlockNumber = 1_000;
+	pub const MilestoneVotingPeriod: BlockNumber = 5;
+	pub const MilestoneApprovalThreshold: Perbill = Perbill::from_percent(50);
+	pub const MilestoneMinTurnout: Perbill = Perbill::from_percent(10);
+	pub static MaxFinalizationWeight: Weight = Weight::max_value();
+	pub const MaxStringLength: u32 = 32;
+	pub const MaxContentLength: u32 = 128;
+	pub const ProjectDepositBase: Balance = 100;
+	pub const ProjectDepositPerByte: Balance = 1;
+	pub const DisputeBond: Balance = 50;
+	pub const DisputePeriod: BlockNumber = 10;
+}
+
+ord_parameter_types! {
+	pub const Approver: AccountId = APPROVER;
+}
+
+impl proposals::Config for Test {
+	type Event = Event;
+	type PalletId = ProposalsPalletId;
+	type Currency = Balances;
+	type AssetId = AssetId;
+	type Assets = Assets;
+	type MaxProposalsPerRound = MaxProposalsPerRound;
+	type MaxMilestonesPerProject = MaxMilestonesPerProject;
+	type MaxUpcomingRounds = MaxUpcomingRounds;
+	type MaxWithdrawalExpiration = MaxWithdrawalExpiration;
+	type MilestoneVotingPeriod = MilestoneVotingPeriod;
+	type MilestoneApprovalThreshold = MilestoneApprovalThreshold;
+	type MilestoneMinTurnout = MilestoneMinTurnout;
+	type MaxFinalizationWeight = MaxFinalizationWeight;
+	type Treasury = ();
+	type MaxStringLength = MaxStringLength;
+	type MaxContentLength = MaxContentLength;
+	type ProjectDepositBase = ProjectDepositBase;
+	type ProjectDepositPerByte = ProjectDepositPerByte;
+	type ScheduleOrigin = EnsureRoot<AccountId>;
+	type ApproveOrigin = EnsureOneOf<AccountId, EnsureRoot<AccountId>, EnsureSignedBy<Approver, AccountId>>;
+	type CancelOrigin = EnsureRoot<AccountId>;
+	type AdminOrigin = EnsureRoot<AccountId>;
+	type DisputeOrigin = EnsureRoot<AccountId>;
+	type DisputeBond = DisputeBond;
+	type DisputePeriod = DisputePeriod;
+	type ScheduledCall = Call;
+	type PalletsOrigin = OriginCaller;
+	type Scheduler = Scheduler;
+	type VoteWeigher = proposals::StandardVoteWeigher<Test>;
+	type WeightInfo = ();
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![
+			(ALICE, INITIAL_BALANCE),
+			(BOB, INITIAL_BALANCE),
+			(CHARLIE, INITIAL_BALANCE),
+			(DAVE, INITIAL_BALANCE),
+			(REGISTRAR, INITIAL_BALANCE),
+		],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+
+	proposals::GenesisConfig::<Test> {
+		init_max_proposal_count_per_round: 5,
+		init_withdrawal_expiration: 100,
+		init_is_identity_required: false,
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+/// Advance to block `n`, running the proposals pallet's `on_initialize` on the way
+pub fn run_to_block(n: BlockNumber) {
+	while System::block_number() < n {
+		let next = System::block_number() + 1;
+		System::set_block_number(next);
+		Scheduler::on_initialize(next);
+		ImbueProposals::on_initialize(next);
+	}
+}
+
+pub fn last_event() -> Event {
+	System::events().pop().expect("Event expected").event
+}
@@ -0,0 +1,2827 @@
+use crate::mock::*;
+use crate::{
+	ActiveRounds, Contributions, Error, Event as ProposalsEvent, FinalizationQueue, FundingCurrency, MilestoneIndex, Milestone, MilestonePayout,
+	LastFinalizedRoundEnd, ProjectContent, ProjectIndex, ProjectMatchingFunds, ProjectState, Projects, ProposedMilestone, Proposals, RoundContributions, RoundIndex,
+	RoundProjects, RefundCursors, Refunds, ReturnedFundsBatch, ReturnedFundsClaim, ReturnedFundsClaimed, UnclaimedFundsDestination, UpcomingRounds, VoteOption, VoteWeighting, VotingRules, WithdrawalExpiries,
+};
+use crate::{BalanceOf, Config, Pallet};
+use codec::Encode;
+use frame_support::{assert_noop, assert_ok, traits::{Currency, Get, GetStorageVersion, Hooks, StorageVersion}};
+use frame_system::RawOrigin;
+use pallet_identity::{Data, IdentityInfo, Judgement};
+use sp_core::H256;
+use sp_runtime::{DispatchError, Perbill};
+use std::convert::TryInto;
+
+fn proposed_milestones(percentages: &[u32]) -> Vec<ProposedMilestone> {
+	percentages
+		.iter()
+		.map(|percentage| ProposedMilestone {
+			name: b"milestone".to_vec(),
+			percentage_to_unlock: *percentage,
+		})
+		.collect()
+}
+
+fn raw(content: &[u8]) -> ProjectContent<Vec<u8>> {
+	ProjectContent::Raw(content.to_vec())
+}
+
+/// How a test's project is created, before it is scheduled in a round
+pub(crate) struct ProjectSetup<Balance, AssetId> {
+	pub percentages: Vec<u32>,
+	pub soft_cap: Balance,
+	pub hard_cap: Balance,
+	pub currency: Option<FundingCurrency<AssetId>>,
+	pub weighting: Option<VoteWeighting<Balance>>,
+}
+
+/// Two milestones of 50% raising up to 10000 of the native currency, without a soft cap
+impl<Balance: From<u32>, AssetId> Default for ProjectSetup<Balance, AssetId> {
+	fn default() -> Self {
+		ProjectSetup {
+			percentages: vec![50, 50],
+			soft_cap: 0u32.into(),
+			hard_cap: 10_000u32.into(),
+			currency: None,
+			weighting: None,
+		}
+	}
+}
+
+/// Create `owner`'s project as `setup` describes it
+/// Generic over the runtime so the XCM tests create their projects the same way.
+pub(crate) fn create_project_with<T: Config>(owner: T::AccountId, setup: ProjectSetup<BalanceOf<T>, T::AssetId>) -> ProjectIndex {
+	let project_key = Pallet::<T>::project_count();
+	assert_ok!(Pallet::<T>::create_project(
+		RawOrigin::Signed(owner.clone()).into(),
+		b"name".to_vec(),
+		raw(b"logo"),
+		raw(b"description"),
+		b"website".to_vec(),
+		proposed_milestones(&setup.percentages),
+		setup.hard_cap,
+		setup.soft_cap,
+	));
+	if let Some(currency) = setup.currency {
+		assert_ok!(Pallet::<T>::set_funding_currency(RawOrigin::Signed(owner.clone()).into(), project_key, currency));
+	}
+	if let Some(weighting) = setup.weighting {
+		assert_ok!(Pallet::<T>::set_vote_weighting(RawOrigin::Signed(owner).into(), project_key, weighting));
+	}
+	project_key
+}
+
+fn create_project(owner: AccountId, percentages: &[u32]) -> ProjectIndex {
+	create_project_with::<Test>(owner, ProjectSetup { percentages: percentages.to_vec(), ..Default::default() })
+}
+
+/// Alice's project as `setup` describes it, in round 0 from block 2 to 10 voting on milestone 0
+fn setup_round(setup: ProjectSetup<Balance, AssetId>) -> (ProjectIndex, RoundIndex) {
+	let project_key = create_project_with::<Test>(ALICE, setup);
+	let round_index = schedule_round(2, 10, vec![(project_key, vec![0])]);
+	run_to_block(3);
+	(project_key, round_index)
+}
+
+fn schedule_round(start: BlockNumber, end: BlockNumber, projects: Vec<(ProjectIndex, Vec<MilestoneIndex>)>) -> RoundIndex {
+	let round_index = ImbueProposals::round_count();
+	assert_ok!(ImbueProposals::schedule_round(Origin::root(), start, end, projects));
+	round_index
+}
+
+fn contribute(who: AccountId, project_key: ProjectIndex, value: Balance) {
+	assert_ok!(ImbueProposals::contribute(Origin::signed(who), project_key, value));
+}
+
+fn submit_milestone(owner: AccountId, project_key: ProjectIndex, milestone_index: MilestoneIndex) {
+	assert_ok!(ImbueProposals::submit_milestone(Origin::signed(owner), project_key, milestone_index, b"evidence".to_vec()));
+}
+
+fn vote(who: AccountId, project_key: ProjectIndex, milestone_index: MilestoneIndex, approve: bool) {
+	assert_ok!(ImbueProposals::vote_on_milestone(Origin::signed(who), project_key, milestone_index, approve));
+}
+
+fn set_identity(who: AccountId, judgement: Judgement<Balance>) {
+	assert_ok!(Identity::add_registrar(Origin::root(), REGISTRAR));
+	assert_ok!(Identity::set_identity(
+		Origin::signed(who),
+		Box::new(IdentityInfo {
+			display: Data::Raw(b"name".to_vec().try_into().unwrap()),
+			..Default::default()
+		})
+	));
+	assert_ok!(Identity::provide_judgement(Origin::signed(REGISTRAR), 0, who, judgement));
+}
+
+/// Bob contributes 1000 and approves milestone 0, then the round ends and is finalised
+fn approved_round() -> (ProjectIndex, RoundIndex) {
+	let (project_key, round_index) = setup_round(Default::default());
+	contribute(BOB, project_key, 1_000);
+	submit_milestone(ALICE, project_key, 0);
+	vote(BOB, project_key, 0, true);
+	run_to_block(11);
+	(project_key, round_index)
+}
+
+/// Cancel a proposal and refund `who`, closing the project
+fn cancel_and_refund(round_index: RoundIndex, project_key: ProjectIndex, who: AccountId) {
+	assert_ok!(ImbueProposals::cancel(Origin::root(), round_index, project_key));
+	assert_ok!(ImbueProposals::refund(Origin::signed(who), round_index, project_key));
+}
+
+#[test]
+fn create_project_works() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[50, 50]);
+
+		assert_eq!(project_key, 0);
+		assert_eq!(ImbueProposals::project_count(), 1);
+		let project = ImbueProposals::projects(project_key);
+		assert_eq!(project.owner, ALICE);
+		assert_eq!(project.milestones.len(), 2);
+		assert_eq!(project.raised_funds, 0);
+		assert_eq!(ImbueProposals::project_voting_rules(project_key), Some(ImbueProposals::default_voting_rules()));
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::ProjectCreated(project_key)));
+	});
+}
+
+#[test]
+fn create_project_rejects_empty_fields() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ImbueProposals::create_project(Origin::signed(ALICE), Vec::new(), raw(b"logo"), raw(b"description"), b"website".to_vec(), proposed_milestones(&[100]), 1_000, 0),
+			Error::<Test>::InvalidParam
+		);
+		assert_noop!(
+			ImbueProposals::create_project(Origin::signed(ALICE), b"name".to_vec(), raw(b""), raw(b"description"), b"website".to_vec(), proposed_milestones(&[100]), 1_000, 0),
+			Error::<Test>::InvalidParam
+		);
+		assert_noop!(
+			ImbueProposals::create_project(Origin::signed(ALICE), b"name".to_vec(), raw(b"logo"), raw(b""), b"website".to_vec(), proposed_milestones(&[100]), 1_000, 0),
+			Error::<Test>::InvalidParam
+		);
+		assert_noop!(
+			ImbueProposals::create_project(Origin::signed(ALICE), b"name".to_vec(), raw(b"logo"), raw(b"description"), Vec::new(), proposed_milestones(&[100]), 1_000, 0),
+			Error::<Test>::InvalidParam
+		);
+	});
+}
+
+#[test]
+fn create_project_enforces_length_limits() {
+	new_test_ext().execute_with(|| {
+		let too_long_string = vec![0; MaxStringLength::get() as usize + 1];
+		let too_long_content = vec![0; MaxContentLength::get() as usize + 1];
+
+		assert_noop!(
+			ImbueProposals::create_project(Origin::signed(ALICE), too_long_string.clone(), raw(b"logo"), raw(b"description"), b"website".to_vec(), proposed_milestones(&[100]), 1_000, 0),
+			Error::<Test>::ParamLimitExceed
+		);
+		assert_noop!(
+			ImbueProposals::create_project(Origin::signed(ALICE), b"name".to_vec(), raw(b"logo"), raw(b"description"), too_long_string.clone(), proposed_milestones(&[100]), 1_000, 0),
+			Error::<Test>::ParamLimitExceed
+		);
+		assert_noop!(
+			ImbueProposals::create_project(Origin::signed(ALICE), b"name".to_vec(), raw(&too_long_content), raw(b"description"), b"website".to_vec(), proposed_milestones(&[100]), 1_000, 0),
+			Error::<Test>::ParamLimitExceed
+		);
+		assert_noop!(
+			ImbueProposals::create_project(Origin::signed(ALICE), b"name".to_vec(), raw(b"logo"), raw(&too_long_content), b"website".to_vec(), proposed_milestones(&[100]), 1_000, 0),
+			Error::<Test>::ParamLimitExceed
+		);
+
+		let mut milestones = proposed_milestones(&[100]);
+		milestones[0].name = too_long_string;
+		assert_noop!(
+			ImbueProposals::create_project(Origin::signed(ALICE), b"name".to_vec(), raw(b"logo"), raw(b"description"), b"website".to_vec(), milestones, 1_000, 0),
+			Error::<Test>::ParamLimitExceed
+		);
+	});
+}
+
+#[test]
+fn create_project_requires_milestones_to_total_100_percent() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ImbueProposals::create_project(Origin::signed(ALICE), b"name".to_vec(), raw(b"logo"), raw(b"description"), b"website".to_vec(), proposed_milestones(&[50, 40]), 1_000, 0),
+			Error::<Test>::MilestonesTotalPercentageMustEqual100
+		);
+		assert_noop!(
+			ImbueProposals::create_project(Origin::signed(ALICE), b"name".to_vec(), raw(b"logo"), raw(b"description"), b"website".to_vec(), proposed_milestones(&[60, 50]), 1_000, 0),
+			Error::<Test>::MilestonesTotalPercentageMustEqual100
+		);
+		assert_noop!(
+			ImbueProposals::create_project(Origin::signed(ALICE), b"name".to_vec(), raw(b"logo"), raw(b"description"), b"website".to_vec(), Vec::new(), 1_000, 0),
+			Error::<Test>::MilestonesTotalPercentageMustEqual100
+		);
+		assert_noop!(
+			ImbueProposals::create_project(Origin::signed(ALICE), b"name".to_vec(), raw(b"logo"), raw(b"description"), b"website".to_vec(), proposed_milestones(&[100, 0]), 1_000, 0),
+			Error::<Test>::InvalidParam
+		);
+	});
+}
+
+#[test]
+fn create_project_limits_milestones() {
+	new_test_ext().execute_with(|| {
+		let max_milestones = MaxMilestonesPerProject::get() as usize;
+		let mut percentages = vec![1; max_milestones + 1];
+		percentages[0] = 100 - max_milestones as u32;
+		assert_noop!(
+			ImbueProposals::create_project(Origin::signed(ALICE), b"name".to_vec(), raw(b"logo"), raw(b"description"), b"website".to_vec(), proposed_milestones(&percentages), 1_000, 0),
+			Error::<Test>::TooManyMilestones
+		);
+
+		percentages.pop();
+		percentages[0] += 1;
+		let project_key = create_project(ALICE, &percentages);
+		assert_eq!(ImbueProposals::projects(project_key).milestones.len(), max_milestones);
+	});
+}
+
+#[test]
+fn create_project_accepts_off_chain_content() {
+	new_test_ext().execute_with(|| {
+		let cid = b"bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi".to_vec();
+		let hash = H256::repeat_byte(1);
+		assert_ok!(ImbueProposals::create_project(
+			Origin::signed(ALICE),
+			b"name".to_vec(),
+			ProjectContent::Ipfs(cid.clone()),
+			ProjectContent::Hash(hash),
+			b"website".to_vec(),
+			proposed_milestones(&[100]),
+			1_000,
+			0,
+		));
+
+		let project = ImbueProposals::get_project(0);
+		assert_eq!(project.logo, ProjectContent::Ipfs(cid));
+		assert_eq!(project.description, ProjectContent::Hash(hash));
+
+		assert_noop!(
+			ImbueProposals::create_project(Origin::signed(ALICE), b"name".to_vec(), ProjectContent::Ipfs(vec![0; 129]), raw(b"description"), b"website".to_vec(), proposed_milestones(&[100]), 1_000, 0),
+			Error::<Test>::ParamLimitExceed
+		);
+		assert_noop!(
+			ImbueProposals::create_project(Origin::signed(ALICE), b"name".to_vec(), ProjectContent::Ipfs(Vec::new()), raw(b"description"), b"website".to_vec(), proposed_milestones(&[100]), 1_000, 0),
+			Error::<Test>::InvalidParam
+		);
+	});
+}
+
+#[test]
+fn create_project_reserves_deposit() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[50, 50]);
+
+		let deposit = ProjectDepositBase::get() + ImbueProposals::projects(project_key).encoded_size() as Balance;
+		assert_eq!(ImbueProposals::project_deposits(project_key), Some((ALICE, deposit)));
+		assert_eq!(Balances::reserved_balance(ALICE), deposit);
+		assert_eq!(Balances::free_balance(ALICE), INITIAL_BALANCE - deposit);
+		assert!(System::events().iter().any(|record| record.event ==
+			Event::ImbueProposals(ProposalsEvent::ProjectDepositReserved(project_key, ALICE, deposit))));
+	});
+}
+
+#[test]
+fn create_project_fails_without_funds_for_deposit() {
+	new_test_ext().execute_with(|| {
+		let poor = 99;
+		assert_noop!(
+			ImbueProposals::create_project(Origin::signed(poor), b"name".to_vec(), raw(b"logo"), raw(b"description"), b"website".to_vec(), proposed_milestones(&[100]), 1_000, 0),
+			pallet_balances::Error::<Test>::InsufficientBalance
+		);
+	});
+}
+
+#[test]
+fn create_project_requires_identity_when_enabled() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ImbueProposals::set_is_identity_required(Origin::root(), true));
+		assert!(ImbueProposals::is_identity_required());
+
+		assert_noop!(
+			ImbueProposals::create_project(Origin::signed(ALICE), b"name".to_vec(), raw(b"logo"), raw(b"description"), b"website".to_vec(), proposed_milestones(&[100]), 1_000, 0),
+			Error::<Test>::IdentityNeeded
+		);
+
+		set_identity(ALICE, Judgement::Reasonable);
+		create_project(ALICE, &[100]);
+	});
+}
+
+#[test]
+fn create_project_rejects_low_quality_identity() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ImbueProposals::set_is_identity_required(Origin::root(), true));
+		set_identity(ALICE, Judgement::LowQuality);
+
+		assert_noop!(
+			ImbueProposals::create_project(Origin::signed(ALICE), b"name".to_vec(), raw(b"logo"), raw(b"description"), b"website".to_vec(), proposed_milestones(&[100]), 1_000, 0),
+			Error::<Test>::IdentityNeeded
+		);
+	});
+}
+
+#[test]
+fn set_is_identity_required_requires_root() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(ImbueProposals::set_is_identity_required(Origin::signed(ALICE), true), DispatchError::BadOrigin);
+	});
+}
+
+#[test]
+fn update_project_works() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[100]);
+		let (_, deposit) = ImbueProposals::project_deposits(project_key).unwrap();
+
+		assert_ok!(ImbueProposals::update_project(
+			Origin::signed(ALICE), project_key, b"new name".to_vec(), raw(b"logo"), raw(b"description"), b"new website".to_vec()
+		));
+
+		let project = ImbueProposals::get_project(project_key);
+		assert_eq!(project.name, b"new name".to_vec());
+		assert_eq!(project.website, b"new website".to_vec());
+		// Eight more bytes are stored, at one unit per byte
+		assert_eq!(ImbueProposals::project_deposits(project_key), Some((ALICE, deposit + 8)));
+		assert_eq!(Balances::reserved_balance(ALICE), deposit + 8);
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::ProjectUpdated(project_key)));
+	});
+}
+
+#[test]
+fn update_project_fails_for_invalid_requests() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[100]);
+
+		assert_noop!(
+			ImbueProposals::update_project(Origin::signed(BOB), project_key, b"name".to_vec(), raw(b"logo"), raw(b"description"), b"website".to_vec()),
+			Error::<Test>::InvalidAccount
+		);
+		assert_noop!(
+			ImbueProposals::update_project(Origin::signed(ALICE), 9, b"name".to_vec(), raw(b"logo"), raw(b"description"), b"website".to_vec()),
+			Error::<Test>::InvalidProjectIndexes
+		);
+		assert_noop!(
+			ImbueProposals::update_project(Origin::signed(ALICE), project_key, Vec::new(), raw(b"logo"), raw(b"description"), b"website".to_vec()),
+			Error::<Test>::InvalidParam
+		);
+
+		schedule_round(2, 10, vec![(project_key, vec![0])]);
+		assert_noop!(
+			ImbueProposals::update_project(Origin::signed(ALICE), project_key, b"name".to_vec(), raw(b"logo"), raw(b"description"), b"website".to_vec()),
+			Error::<Test>::InvalidProjectState
+		);
+	});
+}
+
+#[test]
+fn update_milestones_works() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[50, 50]);
+		let (_, deposit) = ImbueProposals::project_deposits(project_key).unwrap();
+
+		assert_noop!(
+			ImbueProposals::update_milestones(Origin::signed(ALICE), project_key, proposed_milestones(&[50, 40])),
+			Error::<Test>::MilestonesTotalPercentageMustEqual100
+		);
+		assert_noop!(
+			ImbueProposals::update_milestones(Origin::signed(BOB), project_key, proposed_milestones(&[100])),
+			Error::<Test>::InvalidAccount
+		);
+		assert_ok!(ImbueProposals::update_milestones(Origin::signed(ALICE), project_key, proposed_milestones(&[100])));
+
+		let project = ImbueProposals::projects(project_key);
+		assert_eq!(project.milestones.len(), 1);
+		assert_eq!(project.milestones[0].percentage_to_unlock, 100);
+		let (_, new_deposit) = ImbueProposals::project_deposits(project_key).unwrap();
+		assert!(new_deposit < deposit);
+		assert_eq!(Balances::reserved_balance(ALICE), new_deposit);
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::MilestonesUpdated(project_key)));
+	});
+}
+
+#[test]
+fn ownership_transfer_works() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[100]);
+		let (_, deposit) = ImbueProposals::project_deposits(project_key).unwrap();
+
+		assert_noop!(ImbueProposals::propose_owner(Origin::signed(BOB), project_key, Some(BOB)), Error::<Test>::InvalidAccount);
+		assert_ok!(ImbueProposals::propose_owner(Origin::signed(ALICE), project_key, Some(BOB)));
+		assert_eq!(ImbueProposals::pending_owners(project_key), Some(BOB));
+		// Ownership only moves once the new owner accepts
+		assert_eq!(ImbueProposals::projects(project_key).owner, ALICE);
+		assert_noop!(ImbueProposals::accept_ownership(Origin::signed(CHARLIE), project_key), Error::<Test>::NotPendingOwner);
+
+		assert_ok!(ImbueProposals::accept_ownership(Origin::signed(BOB), project_key));
+
+		assert_eq!(ImbueProposals::projects(project_key).owner, BOB);
+		assert_eq!(ImbueProposals::pending_owners(project_key), None);
+		assert_eq!(ImbueProposals::project_deposits(project_key), Some((BOB, deposit)));
+		assert_eq!(Balances::reserved_balance(ALICE), 0);
+		assert_eq!(Balances::reserved_balance(BOB), deposit);
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::OwnershipTransferred(project_key, ALICE, BOB)));
+
+		assert_noop!(
+			ImbueProposals::update_milestones(Origin::signed(ALICE), project_key, proposed_milestones(&[100])),
+			Error::<Test>::InvalidAccount
+		);
+		assert_ok!(ImbueProposals::update_milestones(Origin::signed(BOB), project_key, proposed_milestones(&[100])));
+	});
+}
+
+#[test]
+fn ownership_proposal_can_be_withdrawn() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[100]);
+		assert_ok!(ImbueProposals::propose_owner(Origin::signed(ALICE), project_key, Some(BOB)));
+
+		assert_ok!(ImbueProposals::propose_owner(Origin::signed(ALICE), project_key, None));
+
+		assert_eq!(ImbueProposals::pending_owners(project_key), None);
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::OwnerProposed(project_key, None)));
+		assert_noop!(ImbueProposals::accept_ownership(Origin::signed(BOB), project_key), Error::<Test>::NotPendingOwner);
+	});
+}
+
+#[test]
+fn accept_ownership_fails_without_funds_for_deposit() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[100]);
+		let poor = 99;
+		assert_ok!(ImbueProposals::propose_owner(Origin::signed(ALICE), project_key, Some(poor)));
+
+		assert_noop!(ImbueProposals::accept_ownership(Origin::signed(poor), project_key), pallet_balances::Error::<Test>::InsufficientBalance);
+	});
+}
+
+#[test]
+fn set_funding_currency_fails_for_invalid_requests() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[100]);
+
+		assert_noop!(
+			ImbueProposals::set_funding_currency(Origin::signed(ALICE), project_key, FundingCurrency::Asset(USDT)),
+			Error::<Test>::UnknownAsset
+		);
+		assert_ok!(Assets::force_create(Origin::root(), USDT, ALICE, true, 1));
+		assert_noop!(
+			ImbueProposals::set_funding_currency(Origin::signed(BOB), project_key, FundingCurrency::Asset(USDT)),
+			Error::<Test>::InvalidAccount
+		);
+
+		schedule_round(2, 10, vec![(project_key, vec![0])]);
+		assert_noop!(
+			ImbueProposals::set_funding_currency(Origin::signed(ALICE), project_key, FundingCurrency::Asset(USDT)),
+			Error::<Test>::InvalidProjectState
+		);
+	});
+}
+
+#[test]
+fn asset_contributions_are_withdrawn_in_the_asset() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), USDT, ALICE, true, 1));
+		assert_ok!(Assets::mint(Origin::signed(ALICE), USDT, BOB, 10_000));
+		let (project_key, round_index) = setup_round(ProjectSetup {
+			percentages: vec![100],
+			currency: Some(FundingCurrency::Asset(USDT)),
+			..Default::default()
+		});
+		assert_ok!(ImbueProposals::fund(Origin::signed(CHARLIE), round_index, 1_000));
+		contribute(BOB, project_key, 1_000);
+
+		assert_eq!(Assets::balance(USDT, BOB), 9_000);
+		assert_eq!(Assets::balance(USDT, ImbueProposals::project_account_id(project_key)), 1_000);
+		assert_eq!(Balances::free_balance(BOB), INITIAL_BALANCE);
+
+		submit_milestone(ALICE, project_key, 0);
+		vote(BOB, project_key, 0, true);
+		run_to_block(11);
+		// Matching funds are native, so they stay in the pot
+		assert_eq!(ImbueProposals::project_matching_funds(project_key), 0);
+
+		run_to_block(21);
+		assert_ok!(ImbueProposals::withdraw(Origin::signed(ALICE), round_index, project_key));
+		assert_eq!(Assets::balance(USDT, ALICE), 1_000);
+		assert_eq!(Assets::balance(USDT, ImbueProposals::project_account_id(project_key)), 0);
+	});
+}
+
+#[test]
+fn asset_contributions_are_refunded_in_the_asset() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), USDT, ALICE, true, 1));
+		assert_ok!(Assets::mint(Origin::signed(ALICE), USDT, BOB, 10_000));
+		let (project_key, round_index) = setup_round(ProjectSetup {
+			percentages: vec![100],
+			currency: Some(FundingCurrency::Asset(USDT)),
+			..Default::default()
+		});
+		contribute(BOB, project_key, 300);
+
+		cancel_and_refund(round_index, project_key, BOB);
+
+		assert_eq!(Assets::balance(USDT, BOB), 10_000);
+		assert_eq!(Refunds::<Test>::get((project_key, BOB)), Some(300));
+	});
+}
+
+#[test]
+fn schedule_round_works() {
+	new_test_ext().execute_with(|| {
+		let first = create_project(ALICE, &[50, 50]);
+		let second = create_project(BOB, &[100]);
+
+		let round_index = schedule_round(2, 10, vec![(first, vec![0, 1]), (second, vec![0])]);
+
+		assert_eq!(ImbueProposals::round_count(), 1);
+		assert!(ImbueProposals::rounds(round_index).is_some());
+		assert_eq!(ImbueProposals::get_round_projects(round_index), vec![first, second]);
+		assert!(Proposals::<Test>::contains_key(round_index, first));
+		assert!(Proposals::<Test>::contains_key(round_index, second));
+		assert_eq!(ImbueProposals::round_ends(10), vec![round_index]);
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::RoundCreated(round_index)));
+	});
+}
+
+#[test]
+fn schedule_round_requires_root() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[100]);
+		assert_noop!(
+			ImbueProposals::schedule_round(Origin::signed(ALICE), 2, 10, vec![(project_key, vec![0])]),
+			DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn schedule_round_validates_projects() {
+	new_test_ext().execute_with(|| {
+		for _ in 0..6 {
+			create_project(ALICE, &[100]);
+		}
+
+		assert_noop!(ImbueProposals::schedule_round(Origin::root(), 2, 10, Vec::new()), Error::<Test>::InvalidProjectIndexes);
+		assert_noop!(
+			ImbueProposals::schedule_round(Origin::root(), 2, 10, vec![(6, vec![0])]),
+			Error::<Test>::InvalidProjectIndexes
+		);
+		assert_noop!(
+			ImbueProposals::schedule_round(Origin::root(), 2, 10, vec![(0, vec![0]), (0, vec![0])]),
+			Error::<Test>::InvalidProjectIndexes
+		);
+		assert_noop!(
+			ImbueProposals::schedule_round(Origin::root(), 2, 10, (0..6).map(|project_key| (project_key, vec![0])).collect()),
+			Error::<Test>::ProposalAmountExceed
+		);
+	});
+}
+
+#[test]
+fn schedule_round_validates_milestone_indexes() {
+	new_test_ext().execute_with(|| {
+		let (project_key, _) = approved_round();
+
+		assert_noop!(
+			ImbueProposals::schedule_round(Origin::root(), 12, 20, vec![(project_key, vec![2])]),
+			Error::<Test>::MilestoneDoesNotExist
+		);
+		assert_noop!(
+			ImbueProposals::schedule_round(Origin::root(), 12, 20, vec![(project_key, vec![1, 1])]),
+			Error::<Test>::InvalidParam
+		);
+		assert_noop!(
+			ImbueProposals::schedule_round(Origin::root(), 12, 20, vec![(project_key, vec![0, 1])]),
+			Error::<Test>::MilestoneApproved
+		);
+		schedule_round(12, 20, vec![(project_key, vec![1])]);
+	});
+}
+
+#[test]
+fn schedule_round_validates_blocks() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[100]);
+		run_to_block(5);
+
+		assert_noop!(
+			ImbueProposals::schedule_round(Origin::root(), 10, 10, vec![(project_key, vec![0])]),
+			Error::<Test>::EndTooEarly
+		);
+		assert_noop!(
+			ImbueProposals::schedule_round(Origin::root(), 2, 5, vec![(project_key, vec![0])]),
+			Error::<Test>::EndBlockNumberInvalid
+		);
+	});
+}
+
+#[test]
+fn schedule_round_rejects_overlapping_rounds() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[100]);
+		schedule_round(2, 10, vec![(project_key, vec![0])]);
+
+		assert_noop!(
+			ImbueProposals::schedule_round(Origin::root(), 10, 20, vec![(project_key, vec![0])]),
+			Error::<Test>::StartBlockNumberTooSmall
+		);
+		schedule_round(11, 20, vec![(project_key, vec![0])]);
+	});
+}
+
+#[test]
+fn rounds_are_indexed_until_finalised() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[100]);
+		let round_index = schedule_round(2, 10, vec![(project_key, vec![0])]);
+
+		assert_eq!(ImbueProposals::upcoming_rounds().into_inner(), vec![round_index]);
+		assert_eq!(ImbueProposals::active_rounds(project_key, round_index), Some(()));
+
+		run_to_block(11);
+
+		assert!(ImbueProposals::upcoming_rounds().is_empty());
+		assert_eq!(ImbueProposals::active_rounds(project_key, round_index), None);
+		assert_eq!(ImbueProposals::last_finalized_round_end(), Some(10));
+		// Finalised rounds still may not be overlapped
+		assert_noop!(
+			ImbueProposals::schedule_round(Origin::root(), 5, 20, vec![(project_key, vec![0])]),
+			Error::<Test>::StartBlockNumberTooSmall
+		);
+	});
+}
+
+#[test]
+fn cancelled_rounds_are_dropped_from_the_index() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[100]);
+		let first = schedule_round(2, 10, vec![(project_key, vec![0])]);
+		let second = schedule_round(11, 20, vec![(project_key, vec![0])]);
+
+		assert_ok!(ImbueProposals::cancel_round(Origin::root(), second));
+
+		assert_eq!(ImbueProposals::upcoming_rounds().into_inner(), vec![first]);
+		assert_eq!(ImbueProposals::active_rounds(project_key, second), None);
+		// Only the rounds that were not cancelled bound the start of new ones
+		assert_noop!(
+			ImbueProposals::schedule_round(Origin::root(), 10, 20, vec![(project_key, vec![0])]),
+			Error::<Test>::StartBlockNumberTooSmall
+		);
+		schedule_round(11, 20, vec![(project_key, vec![0])]);
+	});
+}
+
+#[test]
+fn schedule_round_limits_upcoming_rounds() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[100]);
+		for i in 0..MaxUpcomingRounds::get() as u64 {
+			schedule_round(2 + 10 * i, 10 + 10 * i, vec![(project_key, vec![0])]);
+		}
+
+		assert_noop!(
+			ImbueProposals::schedule_round(Origin::root(), 100, 110, vec![(project_key, vec![0])]),
+			Error::<Test>::TooManyUpcomingRounds
+		);
+	});
+}
+
+#[test]
+fn cancel_round_works() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[100]);
+		let round_index = schedule_round(2, 10, vec![(project_key, vec![0])]);
+
+		assert_ok!(ImbueProposals::cancel_round(Origin::root(), round_index));
+
+		assert!(ImbueProposals::rounds(round_index).unwrap().is_canceled);
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::RoundCanceled(round_index)));
+		assert_noop!(ImbueProposals::cancel_round(Origin::root(), round_index), Error::<Test>::RoundCanceled);
+	});
+}
+
+#[test]
+fn cancel_round_cancels_an_earlier_round() {
+	new_test_ext().execute_with(|| {
+		let first_round = schedule_round(2, 10, vec![(create_project(ALICE, &[100]), vec![0])]);
+		let second_round = schedule_round(12, 20, vec![(create_project(BOB, &[100]), vec![0])]);
+
+		assert_ok!(ImbueProposals::cancel_round(Origin::root(), first_round));
+
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::RoundCanceled(first_round)));
+		assert!(ImbueProposals::rounds(first_round).unwrap().is_canceled);
+		assert!(!ImbueProposals::rounds(second_round).unwrap().is_canceled);
+		assert_eq!(ImbueProposals::upcoming_rounds().into_inner(), vec![second_round]);
+	});
+}
+
+#[test]
+fn cancel_round_fails_for_unknown_or_started_rounds() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(ImbueProposals::cancel_round(Origin::root(), 0), Error::<Test>::NoActiveRound);
+
+		let (_, round_index) = setup_round(Default::default());
+		assert_noop!(ImbueProposals::cancel_round(Origin::root(), round_index), Error::<Test>::RoundStarted);
+		assert_noop!(ImbueProposals::cancel_round(Origin::signed(ALICE), round_index), DispatchError::BadOrigin);
+	});
+}
+
+fn emitted(event: ProposalsEvent<Test>) -> bool {
+	System::events().iter().any(|record| record.event == Event::ImbueProposals(event.clone()))
+}
+
+#[test]
+fn scheduler_announces_round_start_and_end() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[100]);
+		let round_index = schedule_round(2, 10, vec![(project_key, vec![0])]);
+		assert!(!emitted(ProposalsEvent::RoundStarted(round_index)));
+
+		run_to_block(2);
+		assert!(emitted(ProposalsEvent::RoundStarted(round_index)));
+		assert!(!emitted(ProposalsEvent::RoundEnded(round_index)));
+
+		run_to_block(10);
+		assert!(emitted(ProposalsEvent::RoundEnded(round_index)));
+	});
+}
+
+#[test]
+fn round_starting_in_the_past_is_started_immediately() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[100]);
+		run_to_block(3);
+
+		let round_index = schedule_round(2, 10, vec![(project_key, vec![0])]);
+
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::RoundStarted(round_index)));
+		run_to_block(10);
+		assert!(emitted(ProposalsEvent::RoundEnded(round_index)));
+	});
+}
+
+#[test]
+fn cancel_round_cancels_scheduled_tasks() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[100]);
+		let round_index = schedule_round(2, 10, vec![(project_key, vec![0])]);
+
+		assert_ok!(ImbueProposals::cancel_round(Origin::root(), round_index));
+		run_to_block(11);
+
+		assert!(!emitted(ProposalsEvent::RoundStarted(round_index)));
+		assert!(!emitted(ProposalsEvent::RoundEnded(round_index)));
+	});
+}
+
+#[test]
+fn start_and_end_round_require_root() {
+	new_test_ext().execute_with(|| {
+		let (_, round_index) = setup_round(Default::default());
+
+		assert_noop!(ImbueProposals::start_round(Origin::signed(ALICE), round_index), DispatchError::BadOrigin);
+		assert_noop!(ImbueProposals::end_round(Origin::signed(ALICE), round_index), DispatchError::BadOrigin);
+		assert_noop!(ImbueProposals::start_round(Origin::root(), round_index + 1), Error::<Test>::NoActiveRound);
+	});
+}
+
+#[test]
+fn start_and_end_round_follow_the_round() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[100]);
+		let round_index = schedule_round(2, 10, vec![(project_key, vec![0])]);
+		assert_noop!(ImbueProposals::start_round(Origin::root(), round_index), Error::<Test>::RoundNotProcessing);
+		assert_noop!(ImbueProposals::end_round(Origin::root(), round_index), Error::<Test>::RoundNotEnded);
+
+		run_to_block(3);
+		assert_noop!(ImbueProposals::start_round(Origin::root(), round_index), Error::<Test>::RoundStarted);
+
+		run_to_block(11);
+		assert_noop!(ImbueProposals::end_round(Origin::root(), round_index), Error::<Test>::RoundEnded);
+	});
+}
+
+#[test]
+fn start_round_rejects_canceled_rounds() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[100]);
+		let round_index = schedule_round(2, 10, vec![(project_key, vec![0])]);
+		assert_ok!(ImbueProposals::cancel_round(Origin::root(), round_index));
+
+		run_to_block(2);
+		assert_noop!(ImbueProposals::start_round(Origin::root(), round_index), Error::<Test>::RoundCanceled);
+	});
+}
+
+#[test]
+fn contribute_in_the_block_the_round_starts() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[100]);
+		let round_index = schedule_round(2, 10, vec![(project_key, vec![0])]);
+
+		run_to_block(2);
+		assert!(emitted(ProposalsEvent::RoundStarted(round_index)));
+		assert_ok!(ImbueProposals::contribute(Origin::signed(BOB), project_key, 100));
+		assert_eq!(ImbueProposals::projects(project_key).raised_funds, 100);
+	});
+}
+
+#[test]
+fn fund_works() {
+	new_test_ext().execute_with(|| {
+		let (_, round_index) = setup_round(Default::default());
+
+		assert_ok!(ImbueProposals::fund(Origin::signed(DAVE), round_index, 500));
+
+		assert_eq!(ImbueProposals::rounds(round_index).unwrap().matching_fund, 500);
+		assert_eq!(Balances::free_balance(ImbueProposals::account_id()), 500);
+		assert_eq!(Balances::free_balance(DAVE), INITIAL_BALANCE - 500);
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::FundSucceed(DAVE, round_index, 500)));
+	});
+}
+
+#[test]
+fn fund_fails_for_invalid_rounds() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(ImbueProposals::fund(Origin::signed(DAVE), 0, 500), Error::<Test>::NoActiveRound);
+
+		let project_key = create_project(ALICE, &[50, 50]);
+		let round_index = schedule_round(2, 10, vec![(project_key, vec![0])]);
+		assert_noop!(ImbueProposals::fund(Origin::signed(DAVE), round_index, 0), Error::<Test>::InvalidParam);
+
+		assert_ok!(ImbueProposals::cancel_round(Origin::root(), round_index));
+		assert_noop!(ImbueProposals::fund(Origin::signed(DAVE), round_index, 500), Error::<Test>::RoundCanceled);
+	});
+}
+
+#[test]
+fn fund_fails_for_finalized_round() {
+	new_test_ext().execute_with(|| {
+		let (_, round_index) = approved_round();
+		assert_noop!(ImbueProposals::fund(Origin::signed(DAVE), round_index, 500), Error::<Test>::RoundFinalized);
+	});
+}
+
+#[test]
+fn contribute_works() {
+	new_test_ext().execute_with(|| {
+		let (project_key, round_index) = setup_round(Default::default());
+
+		contribute(BOB, project_key, 300);
+		contribute(BOB, project_key, 200);
+		contribute(CHARLIE, project_key, 100);
+
+		assert_eq!(Contributions::<Test>::get(project_key, BOB), 500);
+		assert_eq!(Contributions::<Test>::get(project_key, CHARLIE), 100);
+		assert_eq!(RoundContributions::<Test>::get((round_index, project_key), BOB), 500);
+		assert_eq!(ImbueProposals::projects(project_key).raised_funds, 600);
+		// isqrt(500) + isqrt(100)
+		assert_eq!(Proposals::<Test>::get(round_index, project_key).unwrap().contribution_sqrt_sum, 22 + 10);
+		assert_eq!(Balances::free_balance(ImbueProposals::project_account_id(project_key)), 600);
+		assert_eq!(Balances::free_balance(BOB), INITIAL_BALANCE - 500);
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::ContributeSucceed(CHARLIE, project_key, 100, 3)));
+	});
+}
+
+#[test]
+fn contribute_is_trimmed_to_the_hard_cap() {
+	new_test_ext().execute_with(|| {
+		let (project_key, _) = setup_round(ProjectSetup { percentages: vec![100], hard_cap: 500, ..Default::default() });
+		contribute(BOB, project_key, 300);
+
+		contribute(CHARLIE, project_key, 300);
+
+		assert_eq!(ImbueProposals::projects(project_key).raised_funds, 500);
+		assert_eq!(ImbueProposals::contributions(project_key, CHARLIE), 200);
+		assert_eq!(Balances::free_balance(CHARLIE), INITIAL_BALANCE - 200);
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::ContributeSucceed(CHARLIE, project_key, 200, 3)));
+		assert_noop!(ImbueProposals::contribute(Origin::signed(DAVE), project_key, 100), Error::<Test>::HardCapReached);
+	});
+}
+
+#[test]
+fn create_project_validates_caps() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ImbueProposals::create_project(Origin::signed(ALICE), b"name".to_vec(), raw(b"logo"), raw(b"description"), b"website".to_vec(), proposed_milestones(&[100]), 0, 0),
+			Error::<Test>::InvalidParam
+		);
+		assert_noop!(
+			ImbueProposals::create_project(Origin::signed(ALICE), b"name".to_vec(), raw(b"logo"), raw(b"description"), b"website".to_vec(), proposed_milestones(&[100]), 1_000, 1_001),
+			Error::<Test>::InvalidParam
+		);
+	});
+}
+
+#[test]
+fn project_missing_soft_cap_becomes_refundable() {
+	new_test_ext().execute_with(|| {
+		let (project_key, round_index) = setup_round(ProjectSetup { percentages: vec![100], soft_cap: 500, hard_cap: 1_000, ..Default::default() });
+		contribute(BOB, project_key, 300);
+		submit_milestone(ALICE, project_key, 0);
+		vote(BOB, project_key, 0, true);
+
+		run_to_block(11);
+
+		assert!(System::events().iter().any(|record| record.event ==
+			Event::ImbueProposals(ProposalsEvent::SoftCapMissed(round_index, project_key, 300))));
+		assert_eq!(ImbueProposals::projects(project_key).state, ProjectState::Refunding);
+		assert!(!ImbueProposals::projects(project_key).milestones[0].is_approved);
+		assert_eq!(ImbueProposals::project_deposits(project_key), None);
+		assert_ok!(ImbueProposals::refund(Origin::signed(BOB), round_index, project_key));
+		assert_eq!(Balances::free_balance(BOB), INITIAL_BALANCE);
+	});
+}
+
+#[test]
+fn project_reaching_soft_cap_proceeds() {
+	new_test_ext().execute_with(|| {
+		let (project_key, _) = setup_round(ProjectSetup { percentages: vec![100], soft_cap: 500, hard_cap: 1_000, ..Default::default() });
+		contribute(BOB, project_key, 500);
+		submit_milestone(ALICE, project_key, 0);
+		vote(BOB, project_key, 0, true);
+
+		run_to_block(11);
+
+		assert_eq!(ImbueProposals::projects(project_key).state, ProjectState::InMilestones);
+		assert!(ImbueProposals::projects(project_key).milestones[0].is_approved);
+	});
+}
+
+#[test]
+fn contribute_fails_outside_of_a_round() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[50, 50]);
+		assert_noop!(ImbueProposals::contribute(Origin::signed(BOB), project_key, 100), Error::<Test>::NoActiveRound);
+
+		schedule_round(2, 10, vec![(project_key, vec![0])]);
+		assert_noop!(ImbueProposals::contribute(Origin::signed(BOB), project_key, 100), Error::<Test>::RoundNotProcessing);
+
+		run_to_block(3);
+		assert_noop!(ImbueProposals::contribute(Origin::signed(BOB), project_key, 0), Error::<Test>::InvalidParam);
+		assert_noop!(ImbueProposals::contribute(Origin::signed(BOB), 7, 100), Error::<Test>::InvalidParam);
+	});
+}
+
+#[test]
+fn contribute_fails_for_projects_not_in_the_round() {
+	new_test_ext().execute_with(|| {
+		let (project_key, round_index) = setup_round(Default::default());
+		let other_project_key = create_project(BOB, &[100]);
+		assert_noop!(ImbueProposals::contribute(Origin::signed(BOB), other_project_key, 100), Error::<Test>::NoActiveProposal);
+
+		assert_ok!(ImbueProposals::cancel(Origin::root(), round_index, project_key));
+		assert_noop!(ImbueProposals::contribute(Origin::signed(BOB), project_key, 100), Error::<Test>::ProposalCanceled);
+	});
+}
+
+#[test]
+fn contribute_fails_for_closed_project() {
+	new_test_ext().execute_with(|| {
+		let (project_key, round_index) = setup_round(Default::default());
+		contribute(BOB, project_key, 100);
+		cancel_and_refund(round_index, project_key, BOB);
+
+		assert_noop!(
+			ImbueProposals::schedule_round(Origin::root(), 11, 20, vec![(project_key, vec![0])]),
+			Error::<Test>::ProjectClosed
+		);
+	});
+}
+
+#[test]
+fn submit_milestone_works() {
+	new_test_ext().execute_with(|| {
+		let (project_key, _) = setup_round(Default::default());
+
+		submit_milestone(ALICE, project_key, 0);
+
+		let submission = ImbueProposals::milestone_submissions((project_key, 0)).unwrap();
+		assert_eq!(submission.submitted_at, 3);
+		assert_eq!(submission.voting_end, 3 + MilestoneVotingPeriod::get());
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::MilestoneSubmitted(project_key, 0, 8)));
+		assert_noop!(
+			ImbueProposals::submit_milestone(Origin::signed(ALICE), project_key, 0, b"evidence".to_vec()),
+			Error::<Test>::MilestoneAlreadySubmitted
+		);
+	});
+}
+
+#[test]
+fn submit_milestone_requires_a_round_voting_on_it() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[50, 50]);
+		let round_index = schedule_round(2, 10, vec![(project_key, vec![0])]);
+		assert_noop!(
+			ImbueProposals::submit_milestone(Origin::signed(ALICE), project_key, 0, b"evidence".to_vec()),
+			Error::<Test>::RoundNotProcessing
+		);
+
+		run_to_block(3);
+		assert_noop!(
+			ImbueProposals::submit_milestone(Origin::signed(ALICE), project_key, 1, b"evidence".to_vec()),
+			Error::<Test>::MilestoneNotProposed
+		);
+
+		contribute(BOB, project_key, 300);
+		run_to_block(11);
+		assert_eq!(ImbueProposals::projects(project_key).state, ProjectState::Funded);
+		assert_noop!(
+			ImbueProposals::submit_milestone(Origin::signed(ALICE), project_key, 0, b"evidence".to_vec()),
+			Error::<Test>::RoundNotProcessing
+		);
+		assert!(ImbueProposals::rounds(round_index).unwrap().is_finalized);
+	});
+}
+
+#[test]
+fn voting_closes_with_the_round() {
+	new_test_ext().execute_with(|| {
+		let (project_key, _) = setup_round(Default::default());
+		contribute(BOB, project_key, 300);
+
+		run_to_block(7);
+		submit_milestone(ALICE, project_key, 0);
+		assert_eq!(ImbueProposals::milestone_submissions((project_key, 0)).unwrap().voting_end, 9);
+
+		run_to_block(9);
+		vote(BOB, project_key, 0, true);
+		run_to_block(11);
+		assert!(ImbueProposals::projects(project_key).milestones[0].is_approved);
+	});
+}
+
+#[test]
+fn submit_milestone_can_be_resubmitted_after_voting_closes() {
+	new_test_ext().execute_with(|| {
+		let (project_key, _) = setup_round(Default::default());
+		contribute(BOB, project_key, 300);
+		submit_milestone(ALICE, project_key, 0);
+		vote(BOB, project_key, 0, false);
+
+		run_to_block(8);
+		assert_noop!(
+			ImbueProposals::submit_milestone(Origin::signed(ALICE), project_key, 0, b"evidence".to_vec()),
+			Error::<Test>::MilestoneAlreadySubmitted
+		);
+
+		run_to_block(9);
+		submit_milestone(ALICE, project_key, 0);
+		// Voting closes with the round
+		assert_eq!(ImbueProposals::milestone_submissions((project_key, 0)).unwrap().voting_end, 9);
+		assert_eq!(ImbueProposals::milestone_votes((project_key, 0)).nay, 0);
+
+		// The vote on the earlier submission is stale, so voting the same way again counts afresh
+		vote(BOB, project_key, 0, false);
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::VoteComplete(BOB, project_key, 0, false, 9)));
+		assert_eq!(ImbueProposals::milestone_votes((project_key, 0)).nay, 300);
+	});
+}
+
+#[test]
+fn submit_milestone_validates_input() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[50, 50]);
+
+		assert_noop!(
+			ImbueProposals::submit_milestone(Origin::signed(ALICE), project_key, 0, Vec::new()),
+			Error::<Test>::InvalidParam
+		);
+		assert_noop!(
+			ImbueProposals::submit_milestone(Origin::signed(ALICE), project_key, 0, vec![0; MaxStringLength::get() as usize + 1]),
+			Error::<Test>::ParamLimitExceed
+		);
+		assert_noop!(
+			ImbueProposals::submit_milestone(Origin::signed(ALICE), 5, 0, b"evidence".to_vec()),
+			Error::<Test>::InvalidProjectIndexes
+		);
+		assert_noop!(
+			ImbueProposals::submit_milestone(Origin::signed(BOB), project_key, 0, b"evidence".to_vec()),
+			Error::<Test>::InvalidAccount
+		);
+		assert_noop!(
+			ImbueProposals::submit_milestone(Origin::signed(ALICE), project_key, 2, b"evidence".to_vec()),
+			Error::<Test>::MilestoneDoesNotExist
+		);
+	});
+}
+
+#[test]
+fn submit_milestone_fails_for_approved_milestone() {
+	new_test_ext().execute_with(|| {
+		let (project_key, _) = approved_round();
+		assert_noop!(
+			ImbueProposals::submit_milestone(Origin::signed(ALICE), project_key, 0, b"evidence".to_vec()),
+			Error::<Test>::MilestoneApproved
+		);
+	});
+}
+
+#[test]
+fn vote_on_milestone_works() {
+	new_test_ext().execute_with(|| {
+		let (project_key, _) = setup_round(Default::default());
+		contribute(BOB, project_key, 300);
+		contribute(CHARLIE, project_key, 100);
+		submit_milestone(ALICE, project_key, 0);
+
+		vote(BOB, project_key, 0, true);
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::VoteComplete(BOB, project_key, 0, true, 3)));
+		vote(CHARLIE, project_key, 0, false);
+
+		let tally = ImbueProposals::milestone_votes((project_key, 0));
+		assert_eq!(tally.yay, 300);
+		assert_eq!(tally.nay, 100);
+		assert_eq!(ImbueProposals::user_votes((BOB, project_key, 0)).unwrap().option, VoteOption::Yay);
+	});
+}
+
+#[test]
+fn vote_on_milestone_fails_for_same_vote_twice() {
+	new_test_ext().execute_with(|| {
+		let (project_key, _) = setup_round(Default::default());
+		contribute(BOB, project_key, 300);
+		submit_milestone(ALICE, project_key, 0);
+		vote(BOB, project_key, 0, true);
+
+		assert_noop!(
+			ImbueProposals::vote_on_milestone(Origin::signed(BOB), project_key, 0, true),
+			Error::<Test>::VoteAlreadyExists
+		);
+	});
+}
+
+#[test]
+fn changing_a_vote_moves_its_weight() {
+	new_test_ext().execute_with(|| {
+		let (project_key, _) = setup_round(Default::default());
+		contribute(BOB, project_key, 300);
+		submit_milestone(ALICE, project_key, 0);
+		vote(BOB, project_key, 0, true);
+
+		vote(BOB, project_key, 0, false);
+
+		let tally = ImbueProposals::milestone_votes((project_key, 0));
+		assert_eq!(tally.yay, 0);
+		assert_eq!(tally.nay, 300);
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::VoteChanged(BOB, project_key, 0, VoteOption::Nay, 3)));
+	});
+}
+
+#[test]
+fn scheduling_a_round_keeps_milestone_tallies() {
+	new_test_ext().execute_with(|| {
+		let (project_key, _) = setup_round(Default::default());
+		contribute(BOB, project_key, 300);
+		submit_milestone(ALICE, project_key, 0);
+		vote(BOB, project_key, 0, true);
+
+		schedule_round(11, 20, vec![(project_key, vec![0])]);
+		assert_eq!(ImbueProposals::milestone_votes((project_key, 0)).yay, 300);
+
+		vote(BOB, project_key, 0, false);
+		let tally = ImbueProposals::milestone_votes((project_key, 0));
+		assert_eq!(tally.yay, 0);
+		assert_eq!(tally.nay, 300);
+		assert_eq!(ImbueProposals::user_votes((BOB, project_key, 0)).unwrap().submitted_at, 3);
+	});
+}
+
+#[test]
+fn vote_on_milestone_requires_a_contribution_and_submission() {
+	new_test_ext().execute_with(|| {
+		let (project_key, _) = setup_round(Default::default());
+		contribute(BOB, project_key, 300);
+		assert_noop!(
+			ImbueProposals::vote_on_milestone(Origin::signed(BOB), project_key, 0, true),
+			Error::<Test>::MilestoneNotSubmitted
+		);
+		submit_milestone(ALICE, project_key, 0);
+
+		assert_noop!(
+			ImbueProposals::vote_on_milestone(Origin::signed(CHARLIE), project_key, 0, true),
+			Error::<Test>::OnlyContributorsCanVote
+		);
+		assert_noop!(
+			ImbueProposals::vote_on_milestone(Origin::signed(BOB), project_key, 1, true),
+			Error::<Test>::MilestoneNotProposed
+		);
+		assert_noop!(
+			ImbueProposals::vote_on_milestone(Origin::signed(BOB), 5, 0, true),
+			Error::<Test>::InvalidParam
+		);
+
+		run_to_block(9);
+		assert_noop!(
+			ImbueProposals::vote_on_milestone(Origin::signed(BOB), project_key, 0, true),
+			Error::<Test>::MilestoneVotingClosed
+		);
+	});
+}
+
+#[test]
+fn vote_on_milestone_requires_an_active_proposal() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[50, 50]);
+		assert_noop!(
+			ImbueProposals::vote_on_milestone(Origin::signed(BOB), project_key, 0, true),
+			Error::<Test>::NoActiveRound
+		);
+
+		let round_index = schedule_round(2, 10, vec![(project_key, vec![0])]);
+		assert_noop!(
+			ImbueProposals::vote_on_milestone(Origin::signed(BOB), project_key, 0, true),
+			Error::<Test>::RoundNotProcessing
+		);
+
+		run_to_block(3);
+		let other_project_key = create_project(BOB, &[100]);
+		assert_noop!(
+			ImbueProposals::vote_on_milestone(Origin::signed(BOB), other_project_key, 0, true),
+			Error::<Test>::NoActiveProposal
+		);
+
+		contribute(BOB, project_key, 300);
+		submit_milestone(ALICE, project_key, 0);
+		assert_ok!(ImbueProposals::cancel(Origin::root(), round_index, project_key));
+		assert_noop!(
+			ImbueProposals::vote_on_milestone(Origin::signed(BOB), project_key, 0, true),
+			Error::<Test>::ProposalCanceled
+		);
+	});
+}
+
+#[test]
+fn abstain_requires_voting_rules_to_allow_it() {
+	new_test_ext().execute_with(|| {
+		let (project_key, _) = setup_round(Default::default());
+		contribute(BOB, project_key, 300);
+		submit_milestone(ALICE, project_key, 0);
+
+		assert_noop!(
+			ImbueProposals::abstain_on_milestone(Origin::signed(BOB), project_key, 0),
+			Error::<Test>::AbstainNotAllowed
+		);
+
+		let rules = VotingRules { allow_abstain: true, ..ImbueProposals::voting_rules(project_key) };
+		assert_ok!(ImbueProposals::set_project_voting_rules(Origin::root(), project_key, rules));
+		assert_ok!(ImbueProposals::abstain_on_milestone(Origin::signed(BOB), project_key, 0));
+
+		assert_eq!(ImbueProposals::milestone_votes((project_key, 0)).abstain, 300);
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::VoteAbstained(BOB, project_key, 0, 3)));
+	});
+}
+
+#[test]
+fn retract_vote_works() {
+	new_test_ext().execute_with(|| {
+		let (project_key, _) = setup_round(Default::default());
+		contribute(BOB, project_key, 300);
+		submit_milestone(ALICE, project_key, 0);
+
+		assert_noop!(
+			ImbueProposals::retract_vote(Origin::signed(BOB), project_key, 0),
+			Error::<Test>::VoteDoesNotExist
+		);
+
+		vote(BOB, project_key, 0, true);
+		assert_ok!(ImbueProposals::retract_vote(Origin::signed(BOB), project_key, 0));
+
+		assert_eq!(ImbueProposals::milestone_votes((project_key, 0)).yay, 0);
+		assert!(ImbueProposals::user_votes((BOB, project_key, 0)).is_none());
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::VoteRetracted(BOB, project_key, 0, 3)));
+	});
+}
+
+#[test]
+fn set_vote_weighting_works() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[100]);
+		assert_eq!(ImbueProposals::project_vote_weightings(project_key), VoteWeighting::Linear);
+
+		assert_ok!(ImbueProposals::set_vote_weighting(Origin::signed(ALICE), project_key, VoteWeighting::SquareRoot));
+
+		assert_eq!(ImbueProposals::project_vote_weightings(project_key), VoteWeighting::SquareRoot);
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::VoteWeightingSet(project_key, VoteWeighting::SquareRoot)));
+		let round_index = schedule_round(2, 10, vec![(project_key, vec![0])]);
+		assert_eq!(ImbueProposals::round_vote_weightings(round_index, project_key), VoteWeighting::SquareRoot);
+	});
+}
+
+#[test]
+fn set_vote_weighting_fails_for_invalid_requests() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[100]);
+
+		assert_noop!(
+			ImbueProposals::set_vote_weighting(Origin::signed(BOB), project_key, VoteWeighting::SquareRoot),
+			Error::<Test>::InvalidAccount
+		);
+		assert_noop!(
+			ImbueProposals::set_vote_weighting(Origin::signed(ALICE), project_key, VoteWeighting::Capped(0)),
+			Error::<Test>::InvalidParam
+		);
+
+		schedule_round(2, 10, vec![(project_key, vec![0])]);
+		assert_noop!(
+			ImbueProposals::set_vote_weighting(Origin::signed(ALICE), project_key, VoteWeighting::SquareRoot),
+			Error::<Test>::InvalidProjectState
+		);
+	});
+}
+
+#[test]
+fn square_root_weighting_lets_small_contributors_outvote_a_whale() {
+	new_test_ext().execute_with(|| {
+		let (project_key, round_index) = setup_round(ProjectSetup { percentages: vec![100], weighting: Some(VoteWeighting::SquareRoot), ..Default::default() });
+		contribute(BOB, project_key, 4_900);
+		contribute(CHARLIE, project_key, 1_600);
+		contribute(DAVE, project_key, 1_600);
+		submit_milestone(ALICE, project_key, 0);
+		vote(BOB, project_key, 0, false);
+		vote(CHARLIE, project_key, 0, true);
+		vote(DAVE, project_key, 0, true);
+
+		assert_eq!(ImbueProposals::vote_weights(project_key, BOB), 70);
+		assert_eq!(ImbueProposals::total_vote_weights(project_key), 70 + 40 + 40);
+		assert_eq!(ImbueProposals::user_votes((BOB, project_key, 0)).unwrap().weight, 70);
+
+		run_to_block(11);
+
+		// 3200 against 4900 by contribution, but 80 against 70 by weight
+		assert!(ImbueProposals::milestone_votes((project_key, 0)).is_approved);
+		assert!(Proposals::<Test>::get(round_index, project_key).unwrap().is_approved);
+	});
+}
+
+#[test]
+fn capped_weighting_limits_each_account() {
+	new_test_ext().execute_with(|| {
+		let (project_key, _) = setup_round(ProjectSetup { percentages: vec![100], weighting: Some(VoteWeighting::Capped(500)), ..Default::default() });
+		contribute(BOB, project_key, 300);
+		contribute(BOB, project_key, 400);
+		contribute(CHARLIE, project_key, 200);
+
+		assert_eq!(ImbueProposals::contributions(project_key, BOB), 700);
+		assert_eq!(ImbueProposals::vote_weights(project_key, BOB), 500);
+		assert_eq!(ImbueProposals::vote_weights(project_key, CHARLIE), 200);
+		assert_eq!(ImbueProposals::total_vote_weights(project_key), 700);
+	});
+}
+
+#[test]
+fn identity_bonus_only_rewards_verified_accounts() {
+	new_test_ext().execute_with(|| {
+		let (project_key, _) = setup_round(ProjectSetup { percentages: vec![100], weighting: Some(VoteWeighting::IdentityBonus(Perbill::from_percent(50))), ..Default::default() });
+		set_identity(CHARLIE, Judgement::Reasonable);
+		contribute(BOB, project_key, 1_000);
+		contribute(CHARLIE, project_key, 1_000);
+		submit_milestone(ALICE, project_key, 0);
+		vote(CHARLIE, project_key, 0, true);
+
+		assert_eq!(ImbueProposals::vote_weights(project_key, BOB), 1_000);
+		assert_eq!(ImbueProposals::vote_weights(project_key, CHARLIE), 1_500);
+		assert_eq!(ImbueProposals::total_vote_weights(project_key), 2_500);
+		assert_eq!(ImbueProposals::milestone_votes((project_key, 0)).yay, 1_500);
+	});
+}
+
+#[test]
+fn identity_verified_after_contributing_counts_when_voting() {
+	new_test_ext().execute_with(|| {
+		let (project_key, _) = setup_round(ProjectSetup { percentages: vec![100], weighting: Some(VoteWeighting::IdentityBonus(Perbill::from_percent(50))), ..Default::default() });
+		contribute(BOB, project_key, 1_000);
+		contribute(CHARLIE, project_key, 1_000);
+		assert_eq!(ImbueProposals::vote_weights(project_key, CHARLIE), 1_000);
+
+		set_identity(CHARLIE, Judgement::Reasonable);
+		submit_milestone(ALICE, project_key, 0);
+		vote(CHARLIE, project_key, 0, true);
+
+		assert_eq!(ImbueProposals::vote_weights(project_key, CHARLIE), 1_500);
+		assert_eq!(ImbueProposals::total_vote_weights(project_key), 2_500);
+		assert_eq!(ImbueProposals::milestone_votes((project_key, 0)).yay, 1_500);
+	});
+}
+
+#[test]
+fn withdrawal_window_defaults_to_the_maximum() {
+	new_test_ext().execute_with(|| {
+		// As on a chain whose genesis never set it
+		crate::WithdrawalExpiration::<Test>::kill();
+		let (project_key, round_index) = approved_round();
+
+		let proposal = Proposals::<Test>::get(round_index, project_key).unwrap();
+		assert_eq!(proposal.withdrawal_expiration, 10 + MaxWithdrawalExpiration::get());
+	});
+}
+
+#[test]
+fn round_end_approves_milestones_by_vote() {
+	new_test_ext().execute_with(|| {
+		let (project_key, round_index) = approved_round();
+
+		let round = ImbueProposals::rounds(round_index).unwrap();
+		assert!(round.is_finalized);
+		let proposal = Proposals::<Test>::get(round_index, project_key).unwrap();
+		assert!(proposal.is_approved);
+		assert_eq!(proposal.withdrawal_expiration, 10 + 100);
+		assert!(ImbueProposals::projects(project_key).milestones[0].is_approved);
+		assert!(!ImbueProposals::projects(project_key).milestones[1].is_approved);
+		assert!(ImbueProposals::milestone_votes((project_key, 0)).is_approved);
+		assert!(System::events().iter().any(|record| record.event ==
+			Event::ImbueProposals(ProposalsEvent::MilestoneApproved(project_key, 0, ImbueProposals::voting_rules(project_key)))));
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::RoundFinalized(round_index)));
+	});
+}
+
+#[test]
+fn round_end_rejects_milestones_below_turnout() {
+	new_test_ext().execute_with(|| {
+		let (project_key, round_index) = setup_round(Default::default());
+		let rules = VotingRules {
+			min_turnout: Perbill::from_percent(50),
+			approval_threshold: Perbill::from_percent(50),
+			allow_abstain: true,
+		};
+		assert_ok!(ImbueProposals::set_project_voting_rules(Origin::root(), project_key, rules.clone()));
+		contribute(BOB, project_key, 400);
+		contribute(CHARLIE, project_key, 600);
+		submit_milestone(ALICE, project_key, 0);
+		vote(BOB, project_key, 0, true);
+
+		run_to_block(11);
+
+		assert!(!Proposals::<Test>::get(round_index, project_key).unwrap().is_approved);
+		assert!(!ImbueProposals::projects(project_key).milestones[0].is_approved);
+		assert!(System::events().iter().any(|record| record.event ==
+			Event::ImbueProposals(ProposalsEvent::MilestoneRejected(project_key, 0, rules.clone()))));
+	});
+}
+
+#[test]
+fn finalize_round_only_tallies_milestones_submitted_in_the_round() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[50, 50]);
+		let round_index = schedule_round(2, 10, vec![(project_key, vec![0, 1])]);
+		run_to_block(3);
+		contribute(BOB, project_key, 1_000);
+		submit_milestone(ALICE, project_key, 0);
+		vote(BOB, project_key, 0, true);
+
+		run_to_block(11);
+		assert!(ImbueProposals::projects(project_key).milestones[0].is_approved);
+		assert!(Proposals::<Test>::get(round_index, project_key).unwrap().is_approved);
+		// Milestone 1 was never put to a vote, so it is neither approved nor rejected
+		assert!(!System::events().iter().any(|record| matches!(
+			record.event,
+			Event::ImbueProposals(ProposalsEvent::MilestoneRejected(_, 1, _))
+		)));
+		assert_noop!(ImbueProposals::raise_dispute(Origin::signed(ALICE), round_index, project_key, 1), Error::<Test>::MilestoneNotSubmitted);
+	});
+}
+
+#[test]
+fn abstentions_count_towards_turnout() {
+	new_test_ext().execute_with(|| {
+		let (project_key, round_index) = setup_round(Default::default());
+		let rules = VotingRules {
+			min_turnout: Perbill::from_percent(50),
+			approval_threshold: Perbill::from_percent(50),
+			allow_abstain: true,
+		};
+		assert_ok!(ImbueProposals::set_project_voting_rules(Origin::root(), project_key, rules));
+		contribute(BOB, project_key, 400);
+		contribute(CHARLIE, project_key, 600);
+		submit_milestone(ALICE, project_key, 0);
+		vote(BOB, project_key, 0, true);
+		assert_ok!(ImbueProposals::abstain_on_milestone(Origin::signed(CHARLIE), project_key, 0));
+
+		run_to_block(11);
+
+		assert!(Proposals::<Test>::get(round_index, project_key).unwrap().is_approved);
+		assert!(ImbueProposals::projects(project_key).milestones[0].is_approved);
+	});
+}
+
+#[test]
+fn finalization_carries_over_when_out_of_weight() {
+	new_test_ext().execute_with(|| {
+		let (_, round_index) = setup_round(Default::default());
+		let queued = schedule_round(11, 20, vec![(create_project(BOB, &[100]), vec![0])]);
+		FinalizationQueue::<Test>::put(vec![queued]);
+		MaxFinalizationWeight::set(&0);
+
+		// The queued round is finalised even without weight to spare, the round ending now waits
+		run_to_block(10);
+		assert!(ImbueProposals::rounds(queued).unwrap().is_finalized);
+		assert!(!ImbueProposals::rounds(round_index).unwrap().is_finalized);
+		assert_eq!(FinalizationQueue::<Test>::get(), vec![round_index]);
+
+		run_to_block(11);
+		assert!(ImbueProposals::rounds(round_index).unwrap().is_finalized);
+		assert!(FinalizationQueue::<Test>::get().is_empty());
+	});
+}
+
+#[test]
+fn finalization_keeps_earlier_approvals() {
+	new_test_ext().execute_with(|| {
+		let (project_key, _) = setup_round(Default::default());
+		let round_index = schedule_round(11, 20, vec![(project_key, vec![0])]);
+		contribute(BOB, project_key, 1_000);
+		submit_milestone(ALICE, project_key, 0);
+		vote(BOB, project_key, 0, true);
+		run_to_block(11);
+		assert!(ImbueProposals::projects(project_key).milestones[0].is_approved);
+
+		// The tally no longer meets the rules when the later round ends
+		let rules = VotingRules { min_turnout: Perbill::one(), ..ImbueProposals::voting_rules(project_key) };
+		assert_ok!(ImbueProposals::set_project_voting_rules(Origin::root(), project_key, rules));
+		run_to_block(12);
+		contribute(CHARLIE, project_key, 1_000);
+		run_to_block(20);
+
+		assert!(ImbueProposals::rounds(round_index).unwrap().is_finalized);
+		assert!(ImbueProposals::projects(project_key).milestones[0].is_approved);
+	});
+}
+
+#[test]
+fn canceled_round_is_not_finalized() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[100]);
+		let round_index = schedule_round(2, 10, vec![(project_key, vec![0])]);
+		assert_ok!(ImbueProposals::cancel_round(Origin::root(), round_index));
+
+		run_to_block(11);
+		assert!(!ImbueProposals::rounds(round_index).unwrap().is_finalized);
+	});
+}
+
+#[test]
+fn matching_fund_is_split_quadratically() {
+	new_test_ext().execute_with(|| {
+		let first = create_project(ALICE, &[100]);
+		let second = create_project(BOB, &[100]);
+		let round_index = schedule_round(2, 10, vec![(first, vec![0]), (second, vec![0])]);
+		run_to_block(3);
+		assert_ok!(ImbueProposals::fund(Origin::signed(DAVE), round_index, 1_000));
+
+		// One contributor against two contributors of the same amount
+		contribute(BOB, first, 100);
+		contribute(CHARLIE, second, 100);
+		contribute(ALICE, second, 100);
+
+		run_to_block(11);
+
+		assert_eq!(ProjectMatchingFunds::<Test>::get(first), 200);
+		assert_eq!(ProjectMatchingFunds::<Test>::get(second), 800);
+		assert_eq!(Balances::free_balance(ImbueProposals::project_account_id(first)), 100 + 200);
+		assert_eq!(Balances::free_balance(ImbueProposals::project_account_id(second)), 200 + 800);
+		assert!(System::events().iter().any(|record| record.event ==
+			Event::ImbueProposals(ProposalsEvent::MatchingFundDistributed(round_index, second, 800))));
+	});
+}
+
+#[test]
+fn cancelled_round_matching_fund_carries_over() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[100]);
+		let canceled = schedule_round(5, 10, vec![(project_key, vec![0])]);
+		assert_ok!(ImbueProposals::fund(Origin::signed(DAVE), canceled, 500));
+		assert_ok!(ImbueProposals::cancel_round(Origin::root(), canceled));
+		assert_eq!(ImbueProposals::rounds(canceled).unwrap().matching_fund, 0);
+		assert_eq!(ImbueProposals::unallocated_matching_fund(), 500);
+
+		let round_index = schedule_round(11, 20, vec![(project_key, vec![0])]);
+		run_to_block(12);
+		contribute(BOB, project_key, 100);
+		run_to_block(21);
+
+		assert_eq!(ImbueProposals::rounds(round_index).unwrap().matching_fund, 500);
+		assert_eq!(ProjectMatchingFunds::<Test>::get(project_key), 500);
+		assert_eq!(ImbueProposals::unallocated_matching_fund(), 0);
+		assert!(System::events().iter().any(|record| record.event ==
+			Event::ImbueProposals(ProposalsEvent::MatchingFundCarriedOver(round_index, 500))));
+	});
+}
+
+#[test]
+fn undistributed_and_unspent_matching_funds_are_unallocated() {
+	new_test_ext().execute_with(|| {
+		let (project_key, round_index) = setup_round(Default::default());
+		let empty_round = schedule_round(11, 20, vec![(create_project(BOB, &[100]), vec![0])]);
+		assert_ok!(ImbueProposals::fund(Origin::signed(DAVE), round_index, 1_000));
+		assert_ok!(ImbueProposals::fund(Origin::signed(DAVE), empty_round, 300));
+		contribute(BOB, project_key, 100);
+		run_to_block(11);
+		assert_eq!(ProjectMatchingFunds::<Test>::get(project_key), 1_000);
+
+		// Nobody contributed to the second round, so its pool is left for a later one
+		run_to_block(21);
+		assert_eq!(ImbueProposals::unallocated_matching_fund(), 300);
+
+		assert_ok!(ImbueProposals::cancel(Origin::root(), round_index, project_key));
+		assert_eq!(ImbueProposals::unallocated_matching_fund(), 1_300);
+		assert_eq!(Balances::free_balance(ImbueProposals::account_id()), 1_300);
+	});
+}
+
+#[test]
+fn approve_works() {
+	new_test_ext().execute_with(|| {
+		let (project_key, round_index) = approved_round();
+
+		assert_ok!(ImbueProposals::approve(Origin::root(), round_index, project_key, vec![0]));
+
+		assert!(Proposals::<Test>::get(round_index, project_key).unwrap().is_approved);
+		assert!(ImbueProposals::projects(project_key).milestones[0].is_approved);
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::ProposalApproved(round_index, project_key)));
+	});
+}
+
+#[test]
+fn approve_only_counts_passing_milestones_of_the_proposal() {
+	new_test_ext().execute_with(|| {
+		let (project_key, round_index) = rejected_round();
+
+		assert_noop!(ImbueProposals::approve(Origin::root(), round_index, project_key, vec![1]), Error::<Test>::MilestoneNotProposed);
+		assert_noop!(ImbueProposals::approve(Origin::root(), round_index, project_key, vec![0]), Error::<Test>::ProposalNotApproved);
+		assert!(!Proposals::<Test>::get(round_index, project_key).unwrap().is_approved);
+	});
+}
+
+#[test]
+fn approve_does_not_extend_the_withdrawal_window() {
+	new_test_ext().execute_with(|| {
+		let (project_key, round_index) = approved_round();
+		let withdrawal_expiration = Proposals::<Test>::get(round_index, project_key).unwrap().withdrawal_expiration;
+
+		run_to_block(50);
+		assert_ok!(ImbueProposals::approve(Origin::root(), round_index, project_key, vec![0]));
+
+		assert_eq!(Proposals::<Test>::get(round_index, project_key).unwrap().withdrawal_expiration, withdrawal_expiration);
+		assert_eq!(WithdrawalExpiries::<Test>::get(50 + 100 + 1), vec![]);
+	});
+}
+
+#[test]
+fn approve_accepts_only_the_approve_origin() {
+	new_test_ext().execute_with(|| {
+		let (project_key, round_index) = approved_round();
+
+		assert_noop!(ImbueProposals::cancel(Origin::signed(APPROVER), round_index, project_key), DispatchError::BadOrigin);
+		assert_noop!(ImbueProposals::set_withdrawal_expiration(Origin::signed(APPROVER), 10), DispatchError::BadOrigin);
+		assert_noop!(ImbueProposals::schedule_round(Origin::signed(APPROVER), 12, 20, vec![(project_key, vec![1])]), DispatchError::BadOrigin);
+
+		assert_ok!(ImbueProposals::approve(Origin::signed(APPROVER), round_index, project_key, vec![0]));
+		assert!(ImbueProposals::projects(project_key).milestones[0].is_approved);
+	});
+}
+
+#[test]
+fn approve_fails_for_invalid_rounds() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(ImbueProposals::approve(Origin::root(), 0, 0, vec![0]), Error::<Test>::NoActiveRound);
+
+		let (project_key, round_index) = setup_round(Default::default());
+		assert_noop!(ImbueProposals::approve(Origin::signed(ALICE), round_index, project_key, vec![0]), DispatchError::BadOrigin);
+		assert_noop!(ImbueProposals::approve(Origin::root(), round_index, project_key, vec![0]), Error::<Test>::RoundNotEnded);
+
+		run_to_block(11);
+		let other_project_key = create_project(BOB, &[100]);
+		assert_noop!(ImbueProposals::approve(Origin::root(), round_index, other_project_key, vec![0]), Error::<Test>::NoActiveProposal);
+	});
+}
+
+#[test]
+fn approve_fails_for_canceled_round_or_proposal() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[100]);
+		let canceled_round = schedule_round(2, 10, vec![(project_key, vec![0])]);
+		assert_ok!(ImbueProposals::cancel_round(Origin::root(), canceled_round));
+		run_to_block(11);
+		assert_noop!(ImbueProposals::approve(Origin::root(), canceled_round, project_key, vec![0]), Error::<Test>::RoundCanceled);
+
+		let round_index = schedule_round(12, 20, vec![(project_key, vec![0])]);
+		assert_ok!(ImbueProposals::cancel(Origin::root(), round_index, project_key));
+		run_to_block(21);
+		assert_noop!(ImbueProposals::approve(Origin::root(), round_index, project_key, vec![0]), Error::<Test>::ProposalCanceled);
+	});
+}
+
+#[test]
+fn withdraw_works() {
+	new_test_ext().execute_with(|| {
+		let (project_key, round_index) = approved_round();
+		assert_noop!(ImbueProposals::withdraw(Origin::signed(ALICE), round_index, project_key), Error::<Test>::DisputePeriodNotOver);
+
+		run_to_block(21);
+		assert_ok!(ImbueProposals::withdraw(Origin::signed(ALICE), round_index, project_key));
+
+		assert_eq!(Balances::total_balance(&ALICE), INITIAL_BALANCE + 500);
+		assert_eq!(Balances::free_balance(ImbueProposals::project_account_id(project_key)), 500);
+		assert_eq!(ImbueProposals::projects(project_key).withdrawn_funds, 500);
+		assert!(Proposals::<Test>::get(round_index, project_key).unwrap().is_withdrawn);
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::ProposalWithdrawn(round_index, project_key, 500)));
+		assert_eq!(ImbueProposals::milestone_payouts(project_key, 0), Some(MilestonePayout { amount: 500, paid_at: 21 }));
+		assert_eq!(ImbueProposals::get_locked_funds(project_key), 500);
+		// Each milestone is only paid once
+		assert_noop!(ImbueProposals::withdraw(Origin::signed(ALICE), round_index, project_key), Error::<Test>::InvalidParam);
+	});
+}
+
+#[test]
+fn withdraw_tops_up_paid_milestones_after_new_contributions() {
+	new_test_ext().execute_with(|| {
+		let (project_key, round_index) = approved_round();
+		run_to_block(21);
+		assert_ok!(ImbueProposals::withdraw(Origin::signed(ALICE), round_index, project_key));
+
+		let next_round_index = schedule_round(22, 30, vec![(project_key, vec![1])]);
+		run_to_block(23);
+		contribute(CHARLIE, project_key, 1_000);
+		submit_milestone(ALICE, project_key, 1);
+		vote(BOB, project_key, 1, true);
+		vote(CHARLIE, project_key, 1, true);
+		run_to_block(41);
+
+		// Each proposal pays the milestones it approved
+		assert_ok!(ImbueProposals::withdraw(Origin::signed(ALICE), next_round_index, project_key));
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::ProposalWithdrawn(next_round_index, project_key, 1_000)));
+		assert_ok!(ImbueProposals::withdraw(Origin::signed(ALICE), round_index, project_key));
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::ProposalWithdrawn(round_index, project_key, 500)));
+
+		assert_eq!(Balances::total_balance(&ALICE), INITIAL_BALANCE + 2_000);
+		assert_eq!(ImbueProposals::projects(project_key).withdrawn_funds, 2_000);
+		assert_eq!(ImbueProposals::get_locked_funds(project_key), 0);
+		assert_eq!(
+			ImbueProposals::get_milestone_payouts(project_key),
+			vec![
+				(0, MilestonePayout { amount: 1_000, paid_at: 41 }),
+				(1, MilestonePayout { amount: 1_000, paid_at: 41 }),
+			]
+		);
+		assert!(System::events().iter().any(|record| record.event ==
+			Event::ImbueProposals(ProposalsEvent::MilestonePaid(project_key, 0, 500))));
+		assert!(System::events().iter().any(|record| record.event ==
+			Event::ImbueProposals(ProposalsEvent::MilestonePaid(project_key, 1, 1_000))));
+	});
+}
+
+#[test]
+fn withdraw_accumulates_across_milestones() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[50, 25, 25]);
+		let mut start = 2;
+		let mut withdrawn = 0;
+
+		for (milestone_index, unlocked) in [(0, 500), (1, 250), (2, 250)] {
+			let round_index = schedule_round(start, start + 8, vec![(project_key, vec![milestone_index])]);
+			run_to_block(start + 1);
+			if milestone_index == 0 {
+				contribute(BOB, project_key, 1_000);
+			}
+			submit_milestone(ALICE, project_key, milestone_index);
+			vote(BOB, project_key, milestone_index, true);
+			// Past the end of the round and its dispute period
+			run_to_block(start + 19);
+
+			assert_ok!(ImbueProposals::withdraw(Origin::signed(ALICE), round_index, project_key));
+			withdrawn += unlocked;
+			assert_eq!(ImbueProposals::projects(project_key).withdrawn_funds, withdrawn);
+			assert_eq!(Balances::total_balance(&ALICE), INITIAL_BALANCE + withdrawn);
+
+			start += 20;
+		}
+
+		assert_eq!(withdrawn, 1_000);
+	});
+}
+
+#[test]
+fn withdraw_includes_matching_funds() {
+	new_test_ext().execute_with(|| {
+		let (project_key, round_index) = setup_round(Default::default());
+		assert_ok!(ImbueProposals::fund(Origin::signed(DAVE), round_index, 1_000));
+		contribute(BOB, project_key, 1_000);
+		submit_milestone(ALICE, project_key, 0);
+		vote(BOB, project_key, 0, true);
+		run_to_block(21);
+
+		assert_ok!(ImbueProposals::withdraw(Origin::signed(ALICE), round_index, project_key));
+		assert_eq!(Balances::total_balance(&ALICE), INITIAL_BALANCE + 1_000);
+	});
+}
+
+#[test]
+fn withdraw_fails_for_invalid_requests() {
+	new_test_ext().execute_with(|| {
+		let (project_key, round_index) = setup_round(Default::default());
+		contribute(BOB, project_key, 1_000);
+		let other_project_key = create_project(ALICE, &[100]);
+
+		assert_noop!(ImbueProposals::withdraw(Origin::signed(ALICE), round_index, 9), Error::<Test>::InvalidProjectIndexes);
+		assert_noop!(ImbueProposals::withdraw(Origin::signed(BOB), round_index, project_key), Error::<Test>::InvalidAccount);
+		assert_noop!(ImbueProposals::withdraw(Origin::signed(ALICE), 7, project_key), Error::<Test>::NoActiveRound);
+		assert_noop!(ImbueProposals::withdraw(Origin::signed(ALICE), round_index, other_project_key), Error::<Test>::NoActiveProposal);
+		// Nothing has been approved yet
+		assert_noop!(ImbueProposals::withdraw(Origin::signed(ALICE), round_index, project_key), Error::<Test>::ProposalNotApproved);
+	});
+}
+
+/// Bob contributes 1000 and rejects milestone 0, then the round ends and is finalised
+fn rejected_round() -> (ProjectIndex, RoundIndex) {
+	let (project_key, round_index) = setup_round(Default::default());
+	contribute(BOB, project_key, 1_000);
+	submit_milestone(ALICE, project_key, 0);
+	vote(BOB, project_key, 0, false);
+	run_to_block(11);
+	(project_key, round_index)
+}
+
+#[test]
+fn upheld_dispute_overturns_rejected_milestone() {
+	new_test_ext().execute_with(|| {
+		let (project_key, round_index) = rejected_round();
+		let reserved = Balances::reserved_balance(ALICE);
+
+		assert_ok!(ImbueProposals::raise_dispute(Origin::signed(ALICE), round_index, project_key, 0));
+		assert_eq!(Balances::reserved_balance(ALICE), reserved + DisputeBond::get());
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::DisputeRaised(project_key, 0, ALICE, DisputeBond::get())));
+
+		assert_ok!(ImbueProposals::resolve_dispute(Origin::root(), project_key, 0, true));
+		assert_eq!(Balances::reserved_balance(ALICE), reserved);
+		assert_eq!(ImbueProposals::disputes(project_key, 0), None);
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::DisputeResolved(project_key, 0, true)));
+		assert!(ImbueProposals::projects(project_key).milestones[0].is_approved);
+		assert!(Proposals::<Test>::get(round_index, project_key).unwrap().is_approved);
+		assert_eq!(ImbueProposals::projects(project_key).state, ProjectState::InMilestones);
+
+		run_to_block(21);
+		assert_ok!(ImbueProposals::withdraw(Origin::signed(ALICE), round_index, project_key));
+		assert_eq!(ImbueProposals::projects(project_key).withdrawn_funds, 500);
+	});
+}
+
+#[test]
+fn dismissed_dispute_slashes_bond() {
+	new_test_ext().execute_with(|| {
+		let (project_key, round_index) = rejected_round();
+		let reserved = Balances::reserved_balance(ALICE);
+		let total = Balances::total_balance(&ALICE);
+
+		assert_ok!(ImbueProposals::raise_dispute(Origin::signed(ALICE), round_index, project_key, 0));
+		assert_ok!(ImbueProposals::resolve_dispute(Origin::root(), project_key, 0, false));
+
+		assert_eq!(Balances::reserved_balance(ALICE), reserved);
+		assert_eq!(Balances::total_balance(&ALICE), total - DisputeBond::get());
+		assert!(!ImbueProposals::projects(project_key).milestones[0].is_approved);
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::DisputeResolved(project_key, 0, false)));
+	});
+}
+
+#[test]
+fn contributors_who_voted_against_can_dispute_approved_milestone() {
+	new_test_ext().execute_with(|| {
+		let (project_key, round_index) = setup_round(Default::default());
+		contribute(BOB, project_key, 1_000);
+		contribute(CHARLIE, project_key, 100);
+		submit_milestone(ALICE, project_key, 0);
+		vote(BOB, project_key, 0, true);
+		vote(CHARLIE, project_key, 0, false);
+		run_to_block(11);
+		assert!(ImbueProposals::projects(project_key).milestones[0].is_approved);
+
+		assert_noop!(ImbueProposals::raise_dispute(Origin::signed(BOB), round_index, project_key, 0), Error::<Test>::CannotDispute);
+		assert_noop!(ImbueProposals::raise_dispute(Origin::signed(ALICE), round_index, project_key, 0), Error::<Test>::CannotDispute);
+		assert_ok!(ImbueProposals::raise_dispute(Origin::signed(CHARLIE), round_index, project_key, 0));
+
+		// Withdrawals wait for the dispute to be settled
+		run_to_block(21);
+		assert_noop!(ImbueProposals::withdraw(Origin::signed(ALICE), round_index, project_key), Error::<Test>::ProjectDisputed);
+
+		assert_ok!(ImbueProposals::resolve_dispute(Origin::root(), project_key, 0, true));
+		assert!(!ImbueProposals::projects(project_key).milestones[0].is_approved);
+		assert!(!Proposals::<Test>::get(round_index, project_key).unwrap().is_approved);
+		// No milestone is left approved, so the project is back to being funded
+		assert_eq!(ImbueProposals::projects(project_key).state, ProjectState::Funded);
+		assert_noop!(ImbueProposals::withdraw(Origin::signed(ALICE), round_index, project_key), Error::<Test>::ProposalNotApproved);
+	});
+}
+
+#[test]
+fn approved_milestones_are_held_until_the_dispute_period_ends() {
+	new_test_ext().execute_with(|| {
+		let (project_key, round_index) = setup_round(Default::default());
+		contribute(BOB, project_key, 1_000);
+		contribute(CHARLIE, project_key, 100);
+		submit_milestone(ALICE, project_key, 0);
+		vote(BOB, project_key, 0, true);
+		vote(CHARLIE, project_key, 0, false);
+		run_to_block(11);
+		assert_noop!(ImbueProposals::withdraw(Origin::signed(ALICE), round_index, project_key), Error::<Test>::DisputePeriodNotOver);
+
+		// The approval can still be disputed on the last block of the dispute period
+		run_to_block(10 + DisputePeriod::get());
+		assert_noop!(ImbueProposals::withdraw(Origin::signed(ALICE), round_index, project_key), Error::<Test>::DisputePeriodNotOver);
+		assert_ok!(ImbueProposals::raise_dispute(Origin::signed(CHARLIE), round_index, project_key, 0));
+	});
+}
+
+#[test]
+fn stale_votes_cannot_dispute() {
+	new_test_ext().execute_with(|| {
+		let (project_key, round_index) = setup_round(Default::default());
+		contribute(BOB, project_key, 1_000);
+		contribute(CHARLIE, project_key, 100);
+		submit_milestone(ALICE, project_key, 0);
+		vote(CHARLIE, project_key, 0, false);
+
+		// Charlie's vote was on the first submission, the round approves the second
+		run_to_block(3 + MilestoneVotingPeriod::get() + 1);
+		submit_milestone(ALICE, project_key, 0);
+		vote(BOB, project_key, 0, true);
+		run_to_block(11);
+		assert!(ImbueProposals::projects(project_key).milestones[0].is_approved);
+
+		assert_noop!(ImbueProposals::raise_dispute(Origin::signed(CHARLIE), round_index, project_key, 0), Error::<Test>::CannotDispute);
+	});
+}
+
+#[test]
+fn disputed_milestones_wait_for_resolution_to_expire() {
+	new_test_ext().execute_with(|| {
+		let (project_key, round_index) = setup_round(Default::default());
+		contribute(BOB, project_key, 1_000);
+		contribute(CHARLIE, project_key, 100);
+		submit_milestone(ALICE, project_key, 0);
+		vote(BOB, project_key, 0, true);
+		vote(CHARLIE, project_key, 0, false);
+		run_to_block(11);
+		assert_ok!(ImbueProposals::raise_dispute(Origin::signed(CHARLIE), round_index, project_key, 0));
+
+		run_to_block(111);
+		assert_eq!(ImbueProposals::expired_milestones(project_key, 0), None);
+		assert!(ImbueProposals::returned_funds(project_key).is_empty());
+
+		// Dismissing the dispute gives Alice a new window to withdraw in
+		assert_ok!(ImbueProposals::resolve_dispute(Origin::root(), project_key, 0, false));
+		assert_eq!(Proposals::<Test>::get(round_index, project_key).unwrap().withdrawal_expiration, 111 + 100);
+		assert_ok!(ImbueProposals::withdraw(Origin::signed(ALICE), round_index, project_key));
+		assert_eq!(ImbueProposals::projects(project_key).withdrawn_funds, 550);
+	});
+}
+
+#[test]
+fn upheld_dispute_reopens_closed_withdrawal_window() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[50, 50]);
+		let round_index = schedule_round(2, 10, vec![(project_key, vec![0, 1])]);
+		run_to_block(3);
+		contribute(BOB, project_key, 1_000);
+		submit_milestone(ALICE, project_key, 0);
+		submit_milestone(ALICE, project_key, 1);
+		vote(BOB, project_key, 0, true);
+		vote(BOB, project_key, 1, false);
+		run_to_block(11);
+		assert_ok!(ImbueProposals::raise_dispute(Origin::signed(ALICE), round_index, project_key, 1));
+
+		// Milestone 0 expires with the window, milestone 1 is still disputed
+		run_to_block(111);
+		assert_eq!(ImbueProposals::expired_milestones(project_key, 0), Some(500));
+
+		assert_ok!(ImbueProposals::resolve_dispute(Origin::root(), project_key, 1, true));
+		assert!(ImbueProposals::projects(project_key).milestones[1].is_approved);
+		assert_eq!(Proposals::<Test>::get(round_index, project_key).unwrap().withdrawal_expiration, 111 + 100);
+		assert_ok!(ImbueProposals::withdraw(Origin::signed(ALICE), round_index, project_key));
+		assert_eq!(ImbueProposals::projects(project_key).withdrawn_funds, 500);
+
+		// Once the new window closes nothing is left of milestone 1 to return
+		run_to_block(212);
+		assert_eq!(ImbueProposals::expired_milestones(project_key, 1), Some(0));
+	});
+}
+
+#[test]
+fn dispute_fails_for_invalid_requests() {
+	new_test_ext().execute_with(|| {
+		let (project_key, round_index) = setup_round(Default::default());
+		contribute(BOB, project_key, 1_000);
+		assert_noop!(ImbueProposals::raise_dispute(Origin::signed(ALICE), round_index, project_key, 0), Error::<Test>::RoundNotFinalized);
+
+		run_to_block(11);
+		assert_noop!(ImbueProposals::raise_dispute(Origin::signed(ALICE), round_index, project_key, 0), Error::<Test>::MilestoneNotSubmitted);
+		let round_index = schedule_round(12, 20, vec![(project_key, vec![0])]);
+		run_to_block(13);
+		submit_milestone(ALICE, project_key, 0);
+		assert_noop!(ImbueProposals::raise_dispute(Origin::signed(ALICE), round_index, project_key, 0), Error::<Test>::RoundNotFinalized);
+
+		run_to_block(21);
+		assert_noop!(ImbueProposals::raise_dispute(Origin::signed(ALICE), round_index, project_key, 1), Error::<Test>::MilestoneDoesNotExist);
+		assert_noop!(ImbueProposals::raise_dispute(Origin::signed(BOB), round_index, project_key, 0), Error::<Test>::CannotDispute);
+		assert_noop!(ImbueProposals::resolve_dispute(Origin::root(), project_key, 0, true), Error::<Test>::NoDispute);
+
+		assert_ok!(ImbueProposals::raise_dispute(Origin::signed(ALICE), round_index, project_key, 0));
+		assert_noop!(ImbueProposals::raise_dispute(Origin::signed(ALICE), round_index, project_key, 0), Error::<Test>::DisputeAlreadyRaised);
+		assert_noop!(ImbueProposals::resolve_dispute(Origin::signed(ALICE), project_key, 0, true), DispatchError::BadOrigin);
+
+		run_to_block(20 + DisputePeriod::get() + 1);
+		assert_ok!(ImbueProposals::resolve_dispute(Origin::root(), project_key, 0, false));
+		assert_noop!(ImbueProposals::raise_dispute(Origin::signed(ALICE), round_index, project_key, 0), Error::<Test>::DisputePeriodOver);
+	});
+}
+
+#[test]
+fn unclaimed_funds_are_returned_to_contributors_after_expiry() {
+	new_test_ext().execute_with(|| {
+		let (project_key, round_index) = approved_round();
+
+		run_to_block(111);
+
+		assert_noop!(
+			ImbueProposals::withdraw(Origin::signed(ALICE), round_index, project_key),
+			Error::<Test>::WithdrawalExpirationExceed
+		);
+		assert_eq!(ImbueProposals::expired_milestones(project_key, 0), Some(500));
+		assert_eq!(ImbueProposals::returned_funds(project_key).into_inner(), vec![ReturnedFundsBatch { amount: 500, raised_funds: 1_000 }]);
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::UnclaimedFundsReturned(
+			round_index, project_key, 500, UnclaimedFundsDestination::Contributors,
+		)));
+
+		assert_noop!(ImbueProposals::claim_returned_funds(Origin::signed(CHARLIE), project_key), Error::<Test>::OnlyContributorsCanRefund);
+		assert_ok!(ImbueProposals::claim_returned_funds(Origin::signed(BOB), project_key));
+		assert_eq!(Balances::free_balance(BOB), INITIAL_BALANCE - 500);
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::ReturnedFundsClaimed(BOB, project_key, 500)));
+		assert_noop!(ImbueProposals::claim_returned_funds(Origin::signed(BOB), project_key), Error::<Test>::NothingToClaim);
+	});
+}
+
+#[test]
+fn unclaimed_funds_can_be_sent_to_the_treasury() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ImbueProposals::set_unclaimed_funds_policy(Origin::signed(ALICE), UnclaimedFundsDestination::Treasury),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(ImbueProposals::set_unclaimed_funds_policy(Origin::root(), UnclaimedFundsDestination::Treasury));
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::UnclaimedFundsPolicySet(UnclaimedFundsDestination::Treasury)));
+		let (project_key, _) = approved_round();
+		let total_issuance = Balances::total_issuance();
+
+		run_to_block(111);
+
+		assert_eq!(Balances::free_balance(ImbueProposals::project_account_id(project_key)), 500);
+		assert_eq!(Balances::total_issuance(), total_issuance - 500);
+		assert!(ImbueProposals::returned_funds(project_key).is_empty());
+	});
+}
+
+#[test]
+fn only_the_unwithdrawn_part_of_a_milestone_expires() {
+	new_test_ext().execute_with(|| {
+		let (project_key, round_index) = approved_round();
+		run_to_block(21);
+		assert_ok!(ImbueProposals::withdraw(Origin::signed(ALICE), round_index, project_key));
+
+		run_to_block(111);
+
+		assert_eq!(ImbueProposals::expired_milestones(project_key, 0), Some(0));
+		assert!(ImbueProposals::returned_funds(project_key).is_empty());
+		assert_eq!(Balances::free_balance(ImbueProposals::project_account_id(project_key)), 500);
+	});
+}
+
+#[test]
+fn expiry_carries_over_when_out_of_weight() {
+	new_test_ext().execute_with(|| {
+		let (project_key, round_index) = approved_round();
+		MaxFinalizationWeight::set(&0);
+
+		run_to_block(111);
+		assert!(ImbueProposals::expired_milestones(project_key, 0).is_none());
+		assert_eq!(WithdrawalExpiries::<Test>::get(112), vec![(round_index, project_key)]);
+
+		MaxFinalizationWeight::set(&u64::max_value());
+		run_to_block(112);
+		assert_eq!(ImbueProposals::expired_milestones(project_key, 0), Some(500));
+	});
+}
+
+#[test]
+fn refund_excludes_expired_funds() {
+	new_test_ext().execute_with(|| {
+		let (project_key, _) = approved_round();
+		run_to_block(111);
+
+		let next_round_index = schedule_round(112, 120, vec![(project_key, vec![1])]);
+		run_to_block(113);
+		cancel_and_refund(next_round_index, project_key, BOB);
+		assert_eq!(Balances::free_balance(BOB), INITIAL_BALANCE - 500);
+
+		assert_ok!(ImbueProposals::claim_returned_funds(Origin::signed(BOB), project_key));
+		assert_eq!(Balances::free_balance(BOB), INITIAL_BALANCE);
+	});
+}
+
+#[test]
+fn expired_share_of_later_contributions_is_returned() {
+	new_test_ext().execute_with(|| {
+		let (project_key, _) = approved_round();
+		run_to_block(111);
+		assert_eq!(ImbueProposals::expired_milestones(project_key, 0), Some(500));
+
+		let next_round_index = schedule_round(112, 120, vec![(project_key, vec![1])]);
+		run_to_block(113);
+		contribute(CHARLIE, project_key, 1_000);
+		run_to_block(121);
+
+		// Half of Charlie's contribution belongs to the expired milestone, which Alice can no longer withdraw
+		assert_eq!(ImbueProposals::expired_milestones(project_key, 0), Some(1_000));
+		assert!(emitted(ProposalsEvent::UnclaimedFundsReturned(next_round_index, project_key, 500, UnclaimedFundsDestination::Contributors)));
+		assert_eq!(
+			ImbueProposals::returned_funds(project_key).into_inner(),
+			vec![ReturnedFundsBatch { amount: 500, raised_funds: 1_000 }, ReturnedFundsBatch { amount: 500, raised_funds: 2_000 }]
+		);
+	});
+}
+
+#[test]
+fn later_contributors_have_no_share_of_earlier_returned_funds() {
+	new_test_ext().execute_with(|| {
+		let (project_key, _) = approved_round();
+		run_to_block(111);
+
+		// Charlie contributes after the first milestone's funds were returned
+		let next_round_index = schedule_round(112, 120, vec![(project_key, vec![1])]);
+		run_to_block(113);
+		contribute(CHARLIE, project_key, 1_000);
+		assert_noop!(ImbueProposals::claim_returned_funds(Origin::signed(CHARLIE), project_key), Error::<Test>::NothingToClaim);
+
+		// Bob's entitlement does not shrink with Charlie's contribution
+		assert_ok!(ImbueProposals::claim_returned_funds(Origin::signed(BOB), project_key));
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::ReturnedFundsClaimed(BOB, project_key, 500)));
+
+		// Both share the expired milestone's part of Charlie's contribution and the second milestone once it expires
+		submit_milestone(ALICE, project_key, 1);
+		vote(BOB, project_key, 1, true);
+		vote(CHARLIE, project_key, 1, true);
+		run_to_block(121);
+		assert!(ImbueProposals::projects(project_key).milestones[1].is_approved);
+		let expiry = Proposals::<Test>::get(next_round_index, project_key).unwrap().withdrawal_expiration;
+		run_to_block(expiry + 1);
+		assert_eq!(ImbueProposals::expired_milestones(project_key, 1), Some(1_000));
+
+		assert_ok!(ImbueProposals::claim_returned_funds(Origin::signed(CHARLIE), project_key));
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::ReturnedFundsClaimed(CHARLIE, project_key, 750)));
+		assert_ok!(ImbueProposals::claim_returned_funds(Origin::signed(BOB), project_key));
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::ReturnedFundsClaimed(BOB, project_key, 750)));
+	});
+}
+
+#[test]
+fn cancel_works() {
+	new_test_ext().execute_with(|| {
+		let (project_key, round_index) = setup_round(Default::default());
+
+		assert_ok!(ImbueProposals::cancel(Origin::root(), round_index, project_key));
+
+		assert!(Proposals::<Test>::get(round_index, project_key).unwrap().is_canceled);
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::ProposalCanceled(round_index, project_key)));
+		assert_noop!(ImbueProposals::cancel(Origin::root(), round_index, project_key), Error::<Test>::ProposalCanceled);
+	});
+}
+
+#[test]
+fn cancel_fails_for_invalid_requests() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(ImbueProposals::cancel(Origin::root(), 0, 0), Error::<Test>::NoActiveRound);
+
+		let project_key = create_project(ALICE, &[100]);
+		let other_project_key = create_project(BOB, &[100]);
+		let round_index = schedule_round(2, 10, vec![(project_key, vec![0])]);
+		assert_noop!(ImbueProposals::cancel(Origin::signed(ALICE), round_index, project_key), DispatchError::BadOrigin);
+		assert_noop!(ImbueProposals::cancel(Origin::root(), round_index, other_project_key), Error::<Test>::NoActiveProposal);
+
+		assert_ok!(ImbueProposals::cancel_round(Origin::root(), round_index));
+		assert_noop!(ImbueProposals::cancel(Origin::root(), round_index, project_key), Error::<Test>::RoundCanceled);
+	});
+}
+
+#[test]
+fn cancel_fails_for_approved_proposal() {
+	new_test_ext().execute_with(|| {
+		let (project_key, round_index) = approved_round();
+		assert_noop!(ImbueProposals::cancel(Origin::root(), round_index, project_key), Error::<Test>::ProposalApproved);
+	});
+}
+
+#[test]
+fn project_moves_through_its_lifecycle() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[100]);
+		assert_eq!(ImbueProposals::projects(project_key).state, ProjectState::Draft);
+
+		let round_index = schedule_round(2, 10, vec![(project_key, vec![0])]);
+		assert_eq!(ImbueProposals::projects(project_key).state, ProjectState::Fundraising);
+		assert!(System::events().iter().any(|record| record.event == Event::ImbueProposals(
+			ProposalsEvent::ProjectStateChanged(project_key, ProjectState::Draft, ProjectState::Fundraising)
+		)));
+
+		run_to_block(3);
+		contribute(BOB, project_key, 1_000);
+		submit_milestone(ALICE, project_key, 0);
+		vote(BOB, project_key, 0, true);
+		run_to_block(11);
+		assert_eq!(ImbueProposals::projects(project_key).state, ProjectState::InMilestones);
+
+		run_to_block(21);
+		assert_ok!(ImbueProposals::withdraw(Origin::signed(ALICE), round_index, project_key));
+		assert_eq!(ImbueProposals::projects(project_key).state, ProjectState::Completed);
+		assert_noop!(
+			ImbueProposals::schedule_round(Origin::root(), 12, 20, vec![(project_key, vec![0])]),
+			Error::<Test>::ProjectClosed
+		);
+	});
+}
+
+#[test]
+fn project_is_funded_when_round_ends_without_approval() {
+	new_test_ext().execute_with(|| {
+		let (project_key, _) = setup_round(Default::default());
+		contribute(BOB, project_key, 1_000);
+		run_to_block(11);
+
+		assert_eq!(ImbueProposals::projects(project_key).state, ProjectState::Funded);
+
+		schedule_round(12, 20, vec![(project_key, vec![0])]);
+		assert_eq!(ImbueProposals::projects(project_key).state, ProjectState::Fundraising);
+	});
+}
+
+#[test]
+fn cancel_round_returns_projects_to_draft() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[100]);
+		let round_index = schedule_round(2, 10, vec![(project_key, vec![0])]);
+
+		assert_ok!(ImbueProposals::cancel_round(Origin::root(), round_index));
+
+		assert_eq!(ImbueProposals::projects(project_key).state, ProjectState::Draft);
+		assert_noop!(
+			ImbueProposals::submit_milestone(Origin::signed(ALICE), project_key, 0, b"evidence".to_vec()),
+			Error::<Test>::InvalidProjectState
+		);
+	});
+}
+
+#[test]
+fn cancel_closes_project() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[100]);
+		let funded_key = create_project(BOB, &[100]);
+		let draft_key = create_project(CHARLIE, &[100]);
+		let round_index = schedule_round(2, 10, vec![(project_key, vec![0]), (funded_key, vec![0])]);
+		run_to_block(3);
+		contribute(DAVE, funded_key, 100);
+
+		assert_ok!(ImbueProposals::cancel(Origin::root(), round_index, project_key));
+		assert_ok!(ImbueProposals::cancel(Origin::root(), round_index, funded_key));
+
+		// Only projects that raised funds have contributors to refund
+		assert_eq!(ImbueProposals::projects(project_key).state, ProjectState::Cancelled);
+		assert_eq!(ImbueProposals::projects(funded_key).state, ProjectState::Refunding);
+		assert_eq!(ImbueProposals::get_projects_by_state(ProjectState::Cancelled), vec![project_key]);
+		assert_eq!(ImbueProposals::get_projects_by_state(ProjectState::Refunding), vec![funded_key]);
+		assert_eq!(ImbueProposals::get_projects_by_state(ProjectState::Draft), vec![draft_key]);
+	});
+}
+
+#[test]
+fn refund_works() {
+	new_test_ext().execute_with(|| {
+		let (project_key, round_index) = setup_round(Default::default());
+		contribute(BOB, project_key, 300);
+		contribute(CHARLIE, project_key, 100);
+
+		cancel_and_refund(round_index, project_key, BOB);
+
+		assert_eq!(Balances::free_balance(BOB), INITIAL_BALANCE);
+		assert_eq!(Refunds::<Test>::get((project_key, BOB)), Some(300));
+		assert_eq!(ImbueProposals::projects(project_key).state, ProjectState::Refunding);
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::ContributorRefunded(BOB, project_key, 300)));
+		assert_noop!(ImbueProposals::refund(Origin::signed(BOB), round_index, project_key), Error::<Test>::AlreadyRefunded);
+	});
+}
+
+#[test]
+fn refund_releases_deposit_when_closing_project() {
+	new_test_ext().execute_with(|| {
+		let (project_key, round_index) = setup_round(Default::default());
+		contribute(BOB, project_key, 300);
+
+		cancel_and_refund(round_index, project_key, BOB);
+
+		assert_eq!(ImbueProposals::project_deposits(project_key), None);
+		assert_eq!(Balances::reserved_balance(ALICE), 0);
+		assert_eq!(Balances::free_balance(ALICE), INITIAL_BALANCE);
+	});
+}
+
+#[test]
+fn deposit_is_released_when_project_completes() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[100]);
+		let round_index = schedule_round(2, 10, vec![(project_key, vec![0])]);
+		run_to_block(3);
+		contribute(BOB, project_key, 1_000);
+		submit_milestone(ALICE, project_key, 0);
+		vote(BOB, project_key, 0, true);
+		run_to_block(21);
+		let (_, deposit) = ImbueProposals::project_deposits(project_key).unwrap();
+
+		assert_ok!(ImbueProposals::withdraw(Origin::signed(ALICE), round_index, project_key));
+
+		assert_eq!(ImbueProposals::project_deposits(project_key), None);
+		assert_eq!(Balances::reserved_balance(ALICE), 0);
+		assert_eq!(Balances::free_balance(ALICE), INITIAL_BALANCE + 1_000);
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::ProjectDepositReleased(project_key, ALICE, deposit)));
+	});
+}
+
+#[test]
+fn deposit_is_kept_while_milestones_are_outstanding() {
+	new_test_ext().execute_with(|| {
+		let (project_key, round_index) = approved_round();
+		run_to_block(21);
+		assert_ok!(ImbueProposals::withdraw(Origin::signed(ALICE), round_index, project_key));
+
+		assert!(ImbueProposals::project_deposits(project_key).is_some());
+		assert!(Balances::reserved_balance(ALICE) > 0);
+	});
+}
+
+#[test]
+fn cancel_spam_project_slashes_deposit() {
+	new_test_ext().execute_with(|| {
+		let (project_key, round_index) = setup_round(Default::default());
+		contribute(BOB, project_key, 300);
+		let (_, deposit) = ImbueProposals::project_deposits(project_key).unwrap();
+		let total_issuance = Balances::total_issuance();
+
+		assert_noop!(ImbueProposals::cancel_spam_project(Origin::signed(BOB), project_key), DispatchError::BadOrigin);
+		assert_noop!(ImbueProposals::cancel_spam_project(Origin::root(), 9), Error::<Test>::InvalidProjectIndexes);
+		assert_ok!(ImbueProposals::cancel_spam_project(Origin::root(), project_key));
+
+		assert_eq!(ImbueProposals::projects(project_key).state, ProjectState::Refunding);
+		assert_eq!(ImbueProposals::project_deposits(project_key), None);
+		assert_eq!(Balances::reserved_balance(ALICE), 0);
+		assert_eq!(Balances::free_balance(ALICE), INITIAL_BALANCE - deposit);
+		assert_eq!(Balances::total_issuance(), total_issuance - deposit);
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::ProjectDepositSlashed(project_key, ALICE, deposit)));
+		assert_noop!(ImbueProposals::cancel_spam_project(Origin::root(), project_key), Error::<Test>::ProjectClosed);
+
+		// Contributors are refunded as for any closed project
+		assert_ok!(ImbueProposals::refund(Origin::signed(BOB), round_index, project_key));
+		assert_eq!(Balances::free_balance(BOB), INITIAL_BALANCE);
+	});
+}
+
+#[test]
+fn refund_fails_for_invalid_requests() {
+	new_test_ext().execute_with(|| {
+		let (project_key, round_index) = setup_round(Default::default());
+		contribute(BOB, project_key, 300);
+
+		assert_noop!(ImbueProposals::refund(Origin::signed(BOB), round_index, project_key), Error::<Test>::ProposalNotCanceled);
+
+		assert_ok!(ImbueProposals::cancel(Origin::root(), round_index, project_key));
+		assert_noop!(ImbueProposals::refund(Origin::signed(CHARLIE), round_index, project_key), Error::<Test>::OnlyContributorsCanRefund);
+		assert_eq!(ImbueProposals::projects(project_key).state, ProjectState::Refunding);
+	});
+}
+
+#[test]
+fn refund_returns_unspent_share_after_withdrawal() {
+	new_test_ext().execute_with(|| {
+		let (project_key, round_index) = approved_round();
+		run_to_block(21);
+		assert_ok!(ImbueProposals::withdraw(Origin::signed(ALICE), round_index, project_key));
+
+		let next_round_index = schedule_round(22, 30, vec![(project_key, vec![1])]);
+		run_to_block(23);
+		cancel_and_refund(next_round_index, project_key, BOB);
+
+		assert_eq!(Balances::free_balance(BOB), INITIAL_BALANCE - 500);
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::ContributorRefunded(BOB, project_key, 500)));
+	});
+}
+
+#[test]
+fn closed_project_cannot_withdraw_or_submit() {
+	new_test_ext().execute_with(|| {
+		let (project_key, round_index) = setup_round(Default::default());
+		contribute(BOB, project_key, 300);
+		cancel_and_refund(round_index, project_key, BOB);
+
+		assert_noop!(ImbueProposals::withdraw(Origin::signed(ALICE), round_index, project_key), Error::<Test>::ProjectClosed);
+		assert_noop!(
+			ImbueProposals::submit_milestone(Origin::signed(ALICE), project_key, 0, b"evidence".to_vec()),
+			Error::<Test>::ProjectClosed
+		);
+	});
+}
+
+#[test]
+fn refund_contributors_skips_refunded_contributors() {
+	new_test_ext().execute_with(|| {
+		let (project_key, round_index) = setup_round(Default::default());
+		contribute(BOB, project_key, 300);
+		contribute(CHARLIE, project_key, 100);
+		contribute(DAVE, project_key, 200);
+		cancel_and_refund(round_index, project_key, BOB);
+
+		assert_noop!(
+			ImbueProposals::refund_contributors(Origin::signed(ALICE), round_index, project_key, 10),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(ImbueProposals::refund_contributors(Origin::root(), round_index, project_key, 10));
+		assert_eq!(RefundCursors::<Test>::get(project_key), None);
+
+		for who in [BOB, CHARLIE, DAVE] {
+			assert_eq!(Balances::free_balance(who), INITIAL_BALANCE);
+		}
+		assert_eq!(Refunds::<Test>::get((project_key, CHARLIE)), Some(100));
+		assert_eq!(Refunds::<Test>::get((project_key, DAVE)), Some(200));
+		assert_eq!(Balances::free_balance(ImbueProposals::project_account_id(project_key)), 0);
+	});
+}
+
+#[test]
+fn refund_contributors_is_paginated() {
+	new_test_ext().execute_with(|| {
+		let (project_key, round_index) = setup_round(Default::default());
+		contribute(BOB, project_key, 300);
+		contribute(CHARLIE, project_key, 100);
+		assert_ok!(ImbueProposals::cancel(Origin::root(), round_index, project_key));
+
+		assert_ok!(ImbueProposals::refund_contributors(Origin::root(), round_index, project_key, 1));
+		assert_eq!(Refunds::<Test>::iter().count(), 1);
+		assert!(RefundCursors::<Test>::get(project_key).is_some());
+
+		assert_ok!(ImbueProposals::refund_contributors(Origin::root(), round_index, project_key, 1));
+		assert_eq!(Refunds::<Test>::iter().count(), 2);
+		assert_eq!(RefundCursors::<Test>::get(project_key), None);
+		assert_eq!(Balances::free_balance(ImbueProposals::project_account_id(project_key)), 0);
+	});
+}
+
+#[test]
+fn set_max_proposal_count_per_round_works() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(ImbueProposals::set_max_proposal_count_per_round(Origin::root(), 0), Error::<Test>::ParamLimitExceed);
+		assert_noop!(
+			ImbueProposals::set_max_proposal_count_per_round(Origin::root(), MaxProposalsPerRound::get() + 1),
+			Error::<Test>::ParamLimitExceed
+		);
+		assert_noop!(ImbueProposals::set_max_proposal_count_per_round(Origin::signed(ALICE), 3), DispatchError::BadOrigin);
+
+		assert_ok!(ImbueProposals::set_max_proposal_count_per_round(Origin::root(), MaxProposalsPerRound::get()));
+		assert_eq!(ImbueProposals::max_proposal_count_per_round(), MaxProposalsPerRound::get());
+	});
+}
+
+#[test]
+fn set_withdrawal_expiration_works() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(ImbueProposals::set_withdrawal_expiration(Origin::root(), 0), Error::<Test>::InvalidParam);
+		assert_noop!(ImbueProposals::set_withdrawal_expiration(Origin::signed(ALICE), 10), DispatchError::BadOrigin);
+		assert_noop!(
+			ImbueProposals::set_withdrawal_expiration(Origin::root(), MaxWithdrawalExpiration::get() + 1),
+			Error::<Test>::WithdrawalExpirationExceed
+		);
+
+		assert_ok!(ImbueProposals::set_withdrawal_expiration(Origin::root(), 10));
+		assert_eq!(ImbueProposals::withdrawal_expiration(), 10);
+	});
+}
+
+#[test]
+fn set_voting_rules_works() {
+	new_test_ext().execute_with(|| {
+		let rules = VotingRules {
+			min_turnout: Perbill::from_percent(20),
+			approval_threshold: Perbill::from_percent(66),
+			allow_abstain: true,
+		};
+
+		assert_ok!(ImbueProposals::set_default_voting_rules(Origin::root(), rules.clone()));
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::VotingRulesSet(None, rules.clone())));
+		let project_key = create_project(ALICE, &[100]);
+		assert_eq!(ImbueProposals::voting_rules(project_key), rules);
+
+		let project_rules = VotingRules { allow_abstain: false, ..rules };
+		assert_ok!(ImbueProposals::set_project_voting_rules(Origin::root(), project_key, project_rules.clone()));
+		assert_eq!(ImbueProposals::voting_rules(project_key), project_rules);
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::VotingRulesSet(Some(project_key), project_rules.clone())));
+
+		assert_noop!(
+			ImbueProposals::set_project_voting_rules(Origin::root(), 5, project_rules.clone()),
+			Error::<Test>::InvalidProjectIndexes
+		);
+		assert_noop!(ImbueProposals::set_default_voting_rules(Origin::signed(ALICE), project_rules), DispatchError::BadOrigin);
+	});
+}
+
+#[test]
+fn set_voting_rules_rejects_unreachable_thresholds() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[100]);
+		assert_eq!(ImbueProposals::default_voting_rules().min_turnout, MilestoneMinTurnout::get());
+
+		let rules = VotingRules { approval_threshold: Perbill::one(), ..ImbueProposals::default_voting_rules() };
+		assert_noop!(ImbueProposals::set_default_voting_rules(Origin::root(), rules.clone()), Error::<Test>::InvalidParam);
+		assert_noop!(ImbueProposals::set_project_voting_rules(Origin::root(), project_key, rules), Error::<Test>::InvalidParam);
+	});
+}
+
+#[test]
+fn migration_moves_proposals_and_contributions_out_of_rounds() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(0).put::<ImbueProposals>();
+
+		// Encoded in the layout the pallet was first deployed with. Alice's project has a single milestone,
+		// and Bob and Charlie contributed 200 and 100 to it in round 0, from block 2 to block 10.
+		let old_project = hex::decode(concat!(
+			"106e616d65", "106c6f676f", "2c6465736372697074696f6e", "1c77656273697465",
+			"04", "00000000", "00000000", "246d696c6573746f6e65", "64000000", "00",
+			"08", "0200000000000000", "c800000000000000", "0300000000000000", "6400000000000000",
+			"e803000000000000", "0000000000000000", "0100000000000000", "0100000000000000",
+		)).unwrap();
+		let old_round = hex::decode(concat!(
+			"01", "0200000000000000", "0a00000000000000",
+			"04", "00000000", "0400000000",
+			"08", "0200000000000000", "c800000000000000", "0300000000000000", "6400000000000000",
+			"00", "00", "00", "0000000000000000",
+			"00",
+		)).unwrap();
+		// Bob voted for the milestone with the weight of his contribution
+		let old_vote = hex::decode(concat!("c800000000000000", "0000000000000000", "00")).unwrap();
+		let old_user_vote = hex::decode("01").unwrap();
+		frame_support::storage::unhashed::put_raw(&Projects::<Test>::hashed_key_for(0), &old_project);
+		frame_support::storage::unhashed::put_raw(&crate::Rounds::<Test>::hashed_key_for(0), &old_round);
+		frame_support::storage::unhashed::put_raw(&crate::MilestoneVotes::<Test>::hashed_key_for((0, 0)), &old_vote);
+		frame_support::storage::unhashed::put_raw(&crate::UserVotes::<Test>::hashed_key_for((BOB, 0, 0)), &old_user_vote);
+		crate::ProjectCount::<Test>::put(1);
+		crate::RoundCount::<Test>::put(1);
+
+		ImbueProposals::on_runtime_upgrade();
+
+		assert_eq!(ImbueProposals::on_chain_storage_version(), 8);
+		let project = ImbueProposals::projects(0);
+		assert_eq!(project.owner, ALICE);
+		assert_eq!(project.required_funds, 1_000);
+		assert_eq!(project.milestones[0].percentage_to_unlock, 100);
+		assert_eq!(project.raised_funds, 300);
+		assert_eq!(project.state, ProjectState::Fundraising);
+		assert_eq!(Contributions::<Test>::get(0, BOB), 200);
+		assert_eq!(Contributions::<Test>::get(0, CHARLIE), 100);
+		assert_eq!(RoundProjects::<Test>::get(0).into_inner(), vec![0]);
+		assert_eq!(RoundContributions::<Test>::get((0, 0), BOB), 200);
+		// isqrt(200) + isqrt(100)
+		assert_eq!(Proposals::<Test>::get(0, 0).unwrap().contribution_sqrt_sum, 14 + 10);
+		let round = ImbueProposals::rounds(0).unwrap();
+		assert_eq!((round.start, round.end), (2, 10));
+		assert!(!round.is_finalized);
+		assert_eq!(ImbueProposals::round_ends(10), vec![0]);
+		assert_eq!(ImbueProposals::upcoming_rounds().into_inner(), vec![0]);
+		assert_eq!(ImbueProposals::active_rounds(0, 0), Some(()));
+		assert_eq!(ImbueProposals::vote_weights(0, BOB), 200);
+		assert_eq!(ImbueProposals::total_vote_weights(0), 300);
+		assert_eq!(ImbueProposals::milestone_votes((0, 0)).yay, 200);
+		let user_vote = ImbueProposals::user_votes((BOB, 0, 0)).unwrap();
+		assert_eq!((user_vote.option, user_vote.weight, user_vote.submitted_at), (VoteOption::Yay, 200, 0));
+
+		// Running it again is a no-op
+		assert_eq!(crate::migration::v1::migrate::<Test>(), <Test as frame_system::Config>::DbWeight::get().reads(1));
+
+		// The round still opens and is finalised with the tally it had
+		run_to_block(11);
+		assert!(System::events().iter().any(|record| record.event == Event::ImbueProposals(ProposalsEvent::RoundStarted(0))));
+		assert!(ImbueProposals::rounds(0).unwrap().is_finalized);
+		assert!(ImbueProposals::projects(0).milestones[0].is_approved);
+	});
+}
+
+#[test]
+fn migration_finalises_rounds_that_ended_before_the_upgrade() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(0).put::<ImbueProposals>();
+		run_to_block(20);
+
+		// Round 0 ran from block 2 to block 10 without proposals
+		let old_round = hex::decode(concat!("01", "0200000000000000", "0a00000000000000", "00", "00")).unwrap();
+		frame_support::storage::unhashed::put_raw(&crate::Rounds::<Test>::hashed_key_for(0), &old_round);
+		crate::RoundCount::<Test>::put(1);
+
+		ImbueProposals::on_runtime_upgrade();
+
+		assert!(ImbueProposals::rounds(0).unwrap().is_finalized);
+		assert!(ImbueProposals::round_ends(10).is_empty());
+		assert!(ImbueProposals::upcoming_rounds().is_empty());
+		assert_eq!(ImbueProposals::last_finalized_round_end(), Some(10));
+	});
+}
+
+#[test]
+fn migration_seeds_payout_ledger_from_withdrawn_funds() {
+	new_test_ext().execute_with(|| {
+		let (project_key, _) = approved_round();
+		let project = ImbueProposals::projects(project_key);
+		let milestones: Vec<Milestone> = project.milestones.into_iter().map(|milestone| milestone.into_unbounded()).collect();
+		// Pretend milestone 0 was paid before the ledger existed
+		let old_project = (
+			b"name".to_vec(), b"logo".to_vec(), b"description".to_vec(), b"website".to_vec(),
+			milestones, project.raised_funds, project.required_funds, 500u64, ALICE, 1u64, false,
+		);
+		frame_support::storage::unhashed::put_raw(&Projects::<Test>::hashed_key_for(project_key), &old_project.encode());
+		StorageVersion::new(1).put::<ImbueProposals>();
+
+		crate::migration::v2::migrate::<Test>();
+		crate::migration::v3::migrate::<Test>();
+		crate::migration::v4::migrate::<Test>();
+
+		assert_eq!(ImbueProposals::on_chain_storage_version(), 4);
+		assert_eq!(ImbueProposals::projects(project_key).state, ProjectState::InMilestones);
+		assert_eq!(ImbueProposals::milestone_payouts(project_key, 0), Some(MilestonePayout { amount: 500, paid_at: 11 }));
+		run_to_block(21);
+		assert_noop!(ImbueProposals::withdraw(Origin::signed(ALICE), 0, project_key), Error::<Test>::InvalidParam);
+	});
+}
+
+#[test]
+fn migration_bounds_project_text_fields() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(2).put::<ImbueProposals>();
+
+		let milestones = vec![Milestone {
+			project_key: 0,
+			milestone_index: 0,
+			name: vec![b'm'; 40],
+			percentage_to_unlock: 100,
+			is_approved: false,
+		}];
+		let description = vec![b'd'; MaxContentLength::get() as usize + 1];
+		let old_project = (
+			vec![b'n'; 40], b"logo".to_vec(), description.clone(), b"website".to_vec(),
+			milestones, 300u64, 1_000u64, 0u64, ALICE, 1u64, false,
+		);
+		frame_support::storage::unhashed::put_raw(&Projects::<Test>::hashed_key_for(0), &old_project.encode());
+
+		crate::migration::v3::migrate::<Test>();
+		assert_eq!(ImbueProposals::on_chain_storage_version(), 3);
+		crate::migration::v4::migrate::<Test>();
+
+		let project = ImbueProposals::get_project(0);
+		assert_eq!(project.name, vec![b'n'; MaxStringLength::get() as usize]);
+		assert_eq!(project.logo, raw(b"logo"));
+		assert_eq!(project.description, ProjectContent::Hash(H256::from(sp_io::hashing::blake2_256(&description))));
+		assert_eq!(project.milestones[0].name, vec![b'm'; MaxStringLength::get() as usize]);
+		assert_eq!(project.raised_funds, 300);
+		assert_eq!(project.owner, ALICE);
+
+		// Running it again is a no-op
+		assert_eq!(crate::migration::v3::migrate::<Test>(), <Test as frame_system::Config>::DbWeight::get().reads(1));
+	});
+}
+
+#[test]
+fn migration_gives_projects_a_state() {
+	new_test_ext().execute_with(|| {
+		let (approved_key, _) = approved_round();
+		let draft_key = create_project(BOB, &[100]);
+		let refunding_key = create_project(CHARLIE, &[100]);
+		let fundraising_key = create_project(DAVE, &[100]);
+		schedule_round(12, 20, vec![(fundraising_key, vec![0])]);
+		StorageVersion::new(3).put::<ImbueProposals>();
+
+		// Rewrite every project in the v3 layout, with `is_closed` in place of the state
+		for project_key in [approved_key, draft_key, refunding_key, fundraising_key] {
+			let mut encoded = ImbueProposals::projects(project_key).encode();
+			encoded.pop();
+			encoded.push((project_key == refunding_key) as u8);
+			frame_support::storage::unhashed::put_raw(&Projects::<Test>::hashed_key_for(project_key), &encoded);
+		}
+
+		crate::migration::v4::migrate::<Test>();
+
+		assert_eq!(ImbueProposals::on_chain_storage_version(), 4);
+		assert_eq!(ImbueProposals::projects(approved_key).state, ProjectState::InMilestones);
+		assert_eq!(ImbueProposals::projects(draft_key).state, ProjectState::Draft);
+		assert_eq!(ImbueProposals::projects(refunding_key).state, ProjectState::Refunding);
+		assert_eq!(ImbueProposals::projects(fundraising_key).state, ProjectState::Fundraising);
+
+		// Running it again is a no-op
+		assert_eq!(crate::migration::v4::migrate::<Test>(), <Test as frame_system::Config>::DbWeight::get().reads(1));
+	});
+}
+
+#[test]
+fn migration_indexes_upcoming_rounds() {
+	new_test_ext().execute_with(|| {
+		let (approved_key, _) = approved_round();
+		let fundraising_key = create_project(BOB, &[100]);
+		let canceled_key = create_project(CHARLIE, &[100]);
+		let round_index = schedule_round(12, 20, vec![(fundraising_key, vec![0]), (canceled_key, vec![0])]);
+		assert_ok!(ImbueProposals::cancel(Origin::root(), round_index, canceled_key));
+
+		// Forget the indexes, as a chain on storage version 4 never had them
+		UpcomingRounds::<Test>::kill();
+		ActiveRounds::<Test>::remove(fundraising_key, round_index);
+		LastFinalizedRoundEnd::<Test>::kill();
+		StorageVersion::new(4).put::<ImbueProposals>();
+
+		crate::migration::v5::migrate::<Test>();
+
+		assert_eq!(ImbueProposals::on_chain_storage_version(), 5);
+		assert_eq!(ImbueProposals::upcoming_rounds().into_inner(), vec![round_index]);
+		assert_eq!(ImbueProposals::active_rounds(fundraising_key, round_index), Some(()));
+		assert_eq!(ImbueProposals::active_rounds(canceled_key, round_index), None);
+		assert_eq!(ImbueProposals::active_rounds(approved_key, 0), None);
+		assert_eq!(ImbueProposals::last_finalized_round_end(), Some(10));
+
+		// Running it again is a no-op
+		assert_eq!(crate::migration::v5::migrate::<Test>(), <Test as frame_system::Config>::DbWeight::get().reads(1));
+	});
+}
+
+#[test]
+fn migration_bounds_project_milestones() {
+	new_test_ext().execute_with(|| {
+		let project_key = create_project(ALICE, &[50, 50]);
+		let round_index = schedule_round(2, 10, vec![(project_key, vec![0, 1])]);
+		let project = ImbueProposals::projects(project_key);
+		let proposal = ImbueProposals::proposals(round_index, project_key).unwrap();
+		let max_milestones = MaxMilestonesPerProject::get();
+
+		// Written before milestones were bounded, with more of them than now fit
+		let milestones: Vec<_> = (0..max_milestones + 2)
+			.map(|milestone_index| Milestone { milestone_index, ..project.milestones[0].clone() })
+			.collect();
+		let old_project = (
+			project.name.clone(), project.logo.clone(), project.description.clone(), project.website.clone(),
+			milestones, project.raised_funds, project.required_funds, project.withdrawn_funds, project.owner, project.create_block_number, project.state.clone(),
+		);
+		let milestone_indexes: Vec<MilestoneIndex> = (0..max_milestones + 2).collect();
+		let old_proposal = (
+			milestone_indexes, proposal.contribution_sqrt_sum, proposal.is_approved, proposal.is_canceled, proposal.is_withdrawn, proposal.withdrawal_expiration,
+		);
+		frame_support::storage::unhashed::put_raw(&Projects::<Test>::hashed_key_for(project_key), &old_project.encode());
+		frame_support::storage::unhashed::put_raw(&Proposals::<Test>::hashed_key_for(round_index, project_key), &old_proposal.encode());
+		StorageVersion::new(6).put::<ImbueProposals>();
+
+		crate::migration::v7::migrate::<Test>();
+
+		assert_eq!(ImbueProposals::on_chain_storage_version(), 7);
+		let migrated = ImbueProposals::projects(project_key);
+		assert_eq!(migrated.milestones.len(), max_milestones as usize);
+		assert_eq!(migrated.milestones.last().unwrap().milestone_index, max_milestones - 1);
+		assert_eq!(migrated.owner, ALICE);
+		assert_eq!(migrated.state, project.state);
+		let proposal = ImbueProposals::proposals(round_index, project_key).unwrap();
+		assert_eq!(proposal.milestone_indexes.into_inner(), (0..max_milestones).collect::<Vec<_>>());
+
+		// Running it again is a no-op
+		assert_eq!(crate::migration::v7::migrate::<Test>(), <Test as frame_system::Config>::DbWeight::get().reads(1));
+	});
+}
+
+#[test]
+fn migration_batches_returned_funds() {
+	new_test_ext().execute_with(|| {
+		let (project_key, _) = approved_round();
+
+		// Written when returned funds were a single total, of which Bob had claimed part
+		frame_support::storage::unhashed::put_raw(&crate::ReturnedFunds::<Test>::hashed_key_for(project_key), &(500 as Balance).encode());
+		frame_support::storage::unhashed::put_raw(&ReturnedFundsClaimed::<Test>::hashed_key_for(project_key, BOB), &(200 as Balance).encode());
+		StorageVersion::new(7).put::<ImbueProposals>();
+
+		crate::migration::v8::migrate::<Test>();
+
+		assert_eq!(ImbueProposals::on_chain_storage_version(), 8);
+		assert_eq!(ImbueProposals::returned_funds(project_key).into_inner(), vec![ReturnedFundsBatch { amount: 500, raised_funds: 1_000 }]);
+		assert_eq!(ImbueProposals::returned_funds_claimed(project_key, BOB), ReturnedFundsClaim { settled_batches: 1, unclaimed: 300 });
+		assert_ok!(ImbueProposals::claim_returned_funds(Origin::signed(BOB), project_key));
+		assert_eq!(last_event(), Event::ImbueProposals(ProposalsEvent::ReturnedFundsClaimed(BOB, project_key, 300)));
+
+		// Running it again is a no-op
+		assert_eq!(crate::migration::v8::migrate::<Test>(), <Test as frame_system::Config>::DbWeight::get().reads(1));
+	});
+}
@@ -1,7 +1,4 @@
-// This file is part of Substrate.
-
-// Copyright (C) 2021 Parity Technologies (UK) Ltd.
-// SPDX-License-Identifier: Apache-2.0
+// This file is part of Imbue.
 
 // Licensed under the Apache License, Version 2.0 (the "License");
 // you may not use this file except in compliance with the License.
@@ -15,25 +12,24 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-//! Autogenerated weights for pallet_quadratic_funding
+//! Weights for proposals
 //!
-//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 3.0.0
-//! DATE: 2021-05-26, STEPS: `[50, ]`, REPEAT: 20, LOW RANGE: `[]`, HIGH RANGE: `[]`
-//! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("oak-testnet"), DB CACHE: 128
+//! Laid out as generated by the Substrate benchmark CLI from `benchmarking.rs`.
+//! The values below are estimates and have not yet been measured on reference hardware;
+//! regenerate this file with the command below before relying on them.
 
-// Executed Command:
-// ./target/release/oak-testnet
+// Command:
+// ./target/release/imbue-collator
 // benchmark
-// --chain=oak-testnet
+// --chain=dev
 // --steps=50
 // --repeat=20
-// --pallet=pallet_quadratic_funding
+// --pallet=proposals
 // --extrinsic=*
 // --execution=wasm
 // --wasm-execution=compiled
 // --heap-pages=4096
-// --output=pallets/quadratic-funding/src/weights.rs
-// --template=./.maintain/frame-weight-template.hbs
+// --output=pallets/proposals/src/weights.rs
 
 
 #![allow(unused_parens)]
@@ -42,145 +38,432 @@
 use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
 use sp_std::marker::PhantomData;
 
-/// Weight functions needed for pallet_quadratic_funding.
+/// Weight functions needed for proposals.
 pub trait WeightInfo {
-	fn fund() -> Weight;
-	fn create_project() -> Weight;
+	fn create_project(m: u32, ) -> Weight;
+	fn update_project() -> Weight;
+	fn update_milestones(m: u32, ) -> Weight;
+	fn set_funding_currency() -> Weight;
+	fn set_vote_weighting() -> Weight;
+	fn propose_owner() -> Weight;
+	fn accept_ownership() -> Weight;
 	fn schedule_round(s: u32, ) -> Weight;
 	fn cancel_round() -> Weight;
+	fn start_round() -> Weight;
+	fn end_round() -> Weight;
+	fn fund() -> Weight;
+	fn submit_milestone() -> Weight;
+	fn vote_on_milestone() -> Weight;
+	fn abstain_on_milestone() -> Weight;
+	fn retract_vote() -> Weight;
+	fn contribute(u: u32, ) -> Weight;
+	fn approve(m: u32, ) -> Weight;
+	fn withdraw(m: u32, ) -> Weight;
+	fn raise_dispute() -> Weight;
+	fn resolve_dispute() -> Weight;
 	fn cancel() -> Weight;
-	fn set_withdrawal_expiration() -> Weight;
+	fn cancel_spam_project() -> Weight;
+	fn refund() -> Weight;
+	fn refund_contributors(c: u32, ) -> Weight;
+	fn finalize_round(p: u32, m: u32, ) -> Weight;
 	fn set_max_proposal_count_per_round(s: u32, ) -> Weight;
+	fn set_withdrawal_expiration() -> Weight;
+	fn set_default_voting_rules() -> Weight;
+	fn set_project_voting_rules() -> Weight;
 	fn set_is_identity_required() -> Weight;
-	fn contribute() -> Weight;
-	fn finalize_round() -> Weight;
-	fn approve() -> Weight;
-	fn withdraw() -> Weight;
+	fn expire_withdrawal() -> Weight;
+	fn claim_returned_funds() -> Weight;
+	fn set_unclaimed_funds_policy() -> Weight;
+	fn processing_round(u: u32, ) -> Weight;
 }
 
-/// Weights for pallet_quadratic_funding using the Substrate node and recommended hardware.
+/// Weights for proposals using the Substrate node and recommended hardware.
 pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
-	fn fund() -> Weight {
-		(49_000_000 as Weight)
+	fn create_project(m: u32, ) -> Weight {
+		(41_836_000 as Weight)
+			.saturating_add((412_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+	fn update_project() -> Weight {
+		(38_512_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn update_milestones(m: u32, ) -> Weight {
+		(36_904_000 as Weight)
+			.saturating_add((1_126_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(m as Weight)))
+	}
+	fn set_funding_currency() -> Weight {
+		(19_872_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn set_vote_weighting() -> Weight {
+		(17_954_000 as Weight)
 			.saturating_add(T::DbWeight::get().reads(1 as Weight))
 			.saturating_add(T::DbWeight::get().writes(1 as Weight))
 	}
-	fn create_project() -> Weight {
-		(25_000_000 as Weight)
-			.saturating_add(T::DbWeight::get().reads(2 as Weight))
-			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	fn propose_owner() -> Weight {
+		(16_230_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn accept_ownership() -> Weight {
+		(47_318_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
 	}
 	fn schedule_round(s: u32, ) -> Weight {
-		(33_595_000 as Weight)
-			// Standard Error: 1_000
-			.saturating_add((71_000 as Weight).saturating_mul(s as Weight))
-			.saturating_add(T::DbWeight::get().reads(4 as Weight))
-			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+		(41_276_000 as Weight)
+			.saturating_add((9_651_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(T::DbWeight::get().reads(8 as Weight))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(s as Weight)))
+			.saturating_add(T::DbWeight::get().writes(8 as Weight))
+			.saturating_add(T::DbWeight::get().writes((4 as Weight).saturating_mul(s as Weight)))
 	}
 	fn cancel_round() -> Weight {
-		(24_000_000 as Weight)
-			.saturating_add(T::DbWeight::get().reads(2 as Weight))
-			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+		(33_912_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(7 as Weight))
 	}
-	fn cancel() -> Weight {
-		(20_000_000 as Weight)
+	fn start_round() -> Weight {
+		(9_417_000 as Weight)
 			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	fn end_round() -> Weight {
+		(9_388_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	fn fund() -> Weight {
+		(52_481_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn submit_milestone() -> Weight {
+		(31_027_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn vote_on_milestone() -> Weight {
+		(44_809_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(13 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	fn abstain_on_milestone() -> Weight {
+		(45_630_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(13 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	fn retract_vote() -> Weight {
+		(41_262_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn contribute(u: u32, ) -> Weight {
+		(77_212_000 as Weight)
+			.saturating_add((1_846_000 as Weight).saturating_mul(u as Weight))
+			.saturating_add(T::DbWeight::get().reads(13 as Weight))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(u as Weight)))
+			.saturating_add(T::DbWeight::get().writes(9 as Weight))
+	}
+	fn approve(m: u32, ) -> Weight {
+		(38_774_000 as Weight)
+			.saturating_add((1_893_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(m as Weight)))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn withdraw(m: u32, ) -> Weight {
+		(71_382_000 as Weight)
+			.saturating_add((4_003_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul(m as Weight)))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(m as Weight)))
+	}
+	fn raise_dispute() -> Weight {
+		(44_627_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn resolve_dispute() -> Weight {
+		(52_083_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().writes(7 as Weight))
+	}
+	fn cancel() -> Weight {
+		(24_166_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn cancel_spam_project() -> Weight {
+		(52_740_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+	}
+	fn refund() -> Weight {
+		(83_350_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(8 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+	fn refund_contributors(c: u32, ) -> Weight {
+		(35_902_000 as Weight)
+			.saturating_add((47_318_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes((3 as Weight).saturating_mul(c as Weight)))
+	}
+	fn finalize_round(p: u32, m: u32, ) -> Weight {
+		(24_590_000 as Weight)
+			.saturating_add((31_905_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add((6_502_000 as Weight).saturating_mul(p as Weight).saturating_mul(m as Weight))
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().reads((7 as Weight).saturating_mul(p as Weight)))
+			.saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul(p as Weight).saturating_mul(m as Weight)))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes((4 as Weight).saturating_mul(p as Weight)))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(p as Weight).saturating_mul(m as Weight)))
+	}
+	fn set_max_proposal_count_per_round(_s: u32, ) -> Weight {
+		(1_703_000 as Weight)
 			.saturating_add(T::DbWeight::get().writes(1 as Weight))
 	}
 	fn set_withdrawal_expiration() -> Weight {
-		(1_000_000 as Weight)
+		(1_652_000 as Weight)
 			.saturating_add(T::DbWeight::get().writes(1 as Weight))
 	}
-	fn set_max_proposal_count_per_round(_s: u32, ) -> Weight {
-		(1_240_000 as Weight)
+	fn set_default_voting_rules() -> Weight {
+		(2_019_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn set_project_voting_rules() -> Weight {
+		(8_431_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
 			.saturating_add(T::DbWeight::get().writes(1 as Weight))
 	}
 	fn set_is_identity_required() -> Weight {
-		(1_000_000 as Weight)
+		(1_598_000 as Weight)
 			.saturating_add(T::DbWeight::get().writes(1 as Weight))
 	}
-	fn contribute() -> Weight {
-		(55_000_000 as Weight)
-			.saturating_add(T::DbWeight::get().reads(4 as Weight))
-			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	fn expire_withdrawal() -> Weight {
+		(1_204_330_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(205 as Weight))
+			.saturating_add(T::DbWeight::get().writes(102 as Weight))
 	}
-	fn finalize_round() -> Weight {
-		(23_000_000 as Weight)
-			.saturating_add(T::DbWeight::get().reads(1 as Weight))
-			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	fn claim_returned_funds() -> Weight {
+		(62_871_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
 	}
-	fn approve() -> Weight {
-		(26_000_000 as Weight)
-			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+	fn set_unclaimed_funds_policy() -> Weight {
+		(1_714_000 as Weight)
 			.saturating_add(T::DbWeight::get().writes(1 as Weight))
 	}
-	fn withdraw() -> Weight {
-		(66_000_000 as Weight)
-			.saturating_add(T::DbWeight::get().reads(4 as Weight))
-			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	fn processing_round(u: u32, ) -> Weight {
+		(2_231_000 as Weight)
+			.saturating_add((1_594_000 as Weight).saturating_mul(u as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(u as Weight)))
 	}
 }
 
 // For backwards compatibility and tests
 impl WeightInfo for () {
-	fn fund() -> Weight {
-		(49_000_000 as Weight)
+	fn create_project(m: u32, ) -> Weight {
+		(41_836_000 as Weight)
+			.saturating_add((412_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+	}
+	fn update_project() -> Weight {
+		(38_512_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn update_milestones(m: u32, ) -> Weight {
+		(36_904_000 as Weight)
+			.saturating_add((1_126_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(m as Weight)))
+	}
+	fn set_funding_currency() -> Weight {
+		(19_872_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn set_vote_weighting() -> Weight {
+		(17_954_000 as Weight)
 			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
 	}
-	fn create_project() -> Weight {
-		(25_000_000 as Weight)
-			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
-			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	fn propose_owner() -> Weight {
+		(16_230_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn accept_ownership() -> Weight {
+		(47_318_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
 	}
 	fn schedule_round(s: u32, ) -> Weight {
-		(33_595_000 as Weight)
-			// Standard Error: 1_000
-			.saturating_add((71_000 as Weight).saturating_mul(s as Weight))
-			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
-			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+		(41_276_000 as Weight)
+			.saturating_add((9_651_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(RocksDbWeight::get().reads(8 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(s as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(8 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((4 as Weight).saturating_mul(s as Weight)))
 	}
 	fn cancel_round() -> Weight {
-		(24_000_000 as Weight)
-			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
-			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+		(33_912_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(7 as Weight))
 	}
-	fn cancel() -> Weight {
-		(20_000_000 as Weight)
+	fn start_round() -> Weight {
+		(9_417_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+	}
+	fn end_round() -> Weight {
+		(9_388_000 as Weight)
 			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+	}
+	fn fund() -> Weight {
+		(52_481_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn submit_milestone() -> Weight {
+		(31_027_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn vote_on_milestone() -> Weight {
+		(44_809_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(13 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	fn abstain_on_milestone() -> Weight {
+		(45_630_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(13 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	fn retract_vote() -> Weight {
+		(41_262_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(7 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn contribute(u: u32, ) -> Weight {
+		(77_212_000 as Weight)
+			.saturating_add((1_846_000 as Weight).saturating_mul(u as Weight))
+			.saturating_add(RocksDbWeight::get().reads(13 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(u as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(9 as Weight))
+	}
+	fn approve(m: u32, ) -> Weight {
+		(38_774_000 as Weight)
+			.saturating_add((1_893_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(m as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn withdraw(m: u32, ) -> Weight {
+		(71_382_000 as Weight)
+			.saturating_add((4_003_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((3 as Weight).saturating_mul(m as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(m as Weight)))
+	}
+	fn raise_dispute() -> Weight {
+		(44_627_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(7 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn resolve_dispute() -> Weight {
+		(52_083_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(7 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(7 as Weight))
+	}
+	fn cancel() -> Weight {
+		(24_166_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn cancel_spam_project() -> Weight {
+		(52_740_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+	}
+	fn refund() -> Weight {
+		(83_350_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(8 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+	}
+	fn refund_contributors(c: u32, ) -> Weight {
+		(35_902_000 as Weight)
+			.saturating_add((47_318_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((3 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((3 as Weight).saturating_mul(c as Weight)))
+	}
+	fn finalize_round(p: u32, m: u32, ) -> Weight {
+		(24_590_000 as Weight)
+			.saturating_add((31_905_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add((6_502_000 as Weight).saturating_mul(p as Weight).saturating_mul(m as Weight))
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((7 as Weight).saturating_mul(p as Weight)))
+			.saturating_add(RocksDbWeight::get().reads((3 as Weight).saturating_mul(p as Weight).saturating_mul(m as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((4 as Weight).saturating_mul(p as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(p as Weight).saturating_mul(m as Weight)))
+	}
+	fn set_max_proposal_count_per_round(_s: u32, ) -> Weight {
+		(1_703_000 as Weight)
 			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
 	}
 	fn set_withdrawal_expiration() -> Weight {
-		(1_000_000 as Weight)
+		(1_652_000 as Weight)
 			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
 	}
-	fn set_max_proposal_count_per_round(_s: u32, ) -> Weight {
-		(1_240_000 as Weight)
+	fn set_default_voting_rules() -> Weight {
+		(2_019_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn set_project_voting_rules() -> Weight {
+		(8_431_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
 	}
 	fn set_is_identity_required() -> Weight {
-		(1_000_000 as Weight)
+		(1_598_000 as Weight)
 			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
 	}
-	fn contribute() -> Weight {
-		(55_000_000 as Weight)
-			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
-			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	fn expire_withdrawal() -> Weight {
+		(1_204_330_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(205 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(102 as Weight))
 	}
-	fn finalize_round() -> Weight {
-		(23_000_000 as Weight)
-			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
-			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	fn claim_returned_funds() -> Weight {
+		(62_871_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
 	}
-	fn approve() -> Weight {
-		(26_000_000 as Weight)
-			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+	fn set_unclaimed_funds_policy() -> Weight {
+		(1_714_000 as Weight)
 			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
 	}
-	fn withdraw() -> Weight {
-		(66_000_000 as Weight)
-			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
-			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	fn processing_round(u: u32, ) -> Weight {
+		(2_231_000 as Weight)
+			.saturating_add((1_594_000 as Weight).saturating_mul(u as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(u as Weight)))
 	}
-}
\ No newline at end of file
+}
@@ -0,0 +1,185 @@
+use crate::tests::{create_project_with, ProjectSetup};
+use crate::xcm_mock::{
+	para_account_id, parachain, parent_account_id, relay_chain, sibling_account_id, MockNet, ParaA, ParaB, Relay,
+	RelayChainPalletXcm, ALICE, BOB, INITIAL_BALANCE,
+};
+use crate::{MilestoneIndex, ProjectIndex, VoteOption};
+use codec::Encode;
+use frame_support::{assert_ok, traits::Get, weights::Weight};
+use sp_runtime::AccountId32;
+use xcm::latest::prelude::*;
+use xcm::VersionedMultiLocation;
+use xcm_executor::traits::Convert;
+use xcm_simulator::TestExt;
+
+const TRANSACT_WEIGHT: Weight = 2_000_000_000;
+/// What the relay chain account reserve transfers to the remote account on parachain A
+const FUNDING: relay_chain::Balance = 10_000_000_000;
+
+/// What parachain A charges for a `transact` message: its `WithdrawAsset`, `BuyExecution`,
+/// `DescendOrigin` and `Transact` instructions plus the weight the call may use, at one unit per weight
+fn transact_fee() -> parachain::Balance {
+	(4 * parachain::UnitWeightCost::get() + TRANSACT_WEIGHT) as parachain::Balance
+}
+
+/// The account a location dispatches `Transact` calls as on parachain A
+fn derived_account(location: MultiLocation) -> AccountId32 {
+	ParaA::execute_with(|| parachain::LocationToAccountId::convert_ref(&location).unwrap())
+}
+
+/// Alice's single milestone project on parachain A, fundraising in round 0 from block 2 to 10
+fn setup_round() -> ProjectIndex {
+	ParaA::execute_with(|| {
+		let project_key = create_project_with::<parachain::Runtime>(ALICE, ProjectSetup { percentages: vec![100], ..Default::default() });
+		assert_ok!(parachain::ImbueProposals::schedule_round(parachain::Origin::root(), 2, 10, vec![(project_key, vec![0])]));
+		parachain::run_to_block(3);
+		project_key
+	})
+}
+
+/// Have Bob reserve transfer relay chain funds to the account `location` dispatches as on parachain A,
+/// returning the account and what it received after paying for the transfer's execution
+fn fund_derived_account(location: MultiLocation) -> (AccountId32, parachain::Balance) {
+	let account = derived_account(location);
+	Relay::execute_with(|| {
+		assert_ok!(RelayChainPalletXcm::reserve_transfer_assets(
+			relay_chain::Origin::signed(BOB),
+			Box::new(VersionedMultiLocation::from(MultiLocation::from(Parachain(1)))),
+			Box::new(VersionedMultiLocation::from(MultiLocation::from(Junction::AccountId32 { network: NetworkId::Any, id: account.clone().into() }))),
+			Box::new((Here, FUNDING).into()),
+			0,
+		));
+		assert_eq!(relay_chain::Balances::free_balance(&BOB), INITIAL_BALANCE - FUNDING);
+		assert_eq!(relay_chain::Balances::free_balance(&para_account_id(1)), INITIAL_BALANCE + FUNDING);
+	});
+	let balance = ParaA::execute_with(|| parachain::Balances::free_balance(&account));
+	assert!(balance > 0 && balance <= FUNDING);
+	(account, balance)
+}
+
+fn transact_instruction(call: crate::Call<parachain::Runtime>) -> Instruction<()> {
+	Transact {
+		origin_type: OriginKind::SovereignAccount,
+		require_weight_at_most: TRANSACT_WEIGHT,
+		call: parachain::Call::ImbueProposals(call).encode().into(),
+	}
+}
+
+/// A message asking parachain A to dispatch `call` as the derived account of `who` on the sending chain.
+/// The barrier only lets through messages that pay for their execution first, so the sending chain pays
+/// from its own account on parachain A before descending to `who`.
+fn transact(who: AccountId32, call: crate::Call<parachain::Runtime>) -> Xcm<()> {
+	let fees: MultiAsset = (Parent, transact_fee()).into();
+	Xcm(vec![
+		WithdrawAsset(fees.clone().into()),
+		BuyExecution { fees, weight_limit: Limited(transact_fee() as Weight) },
+		DescendOrigin(X1(account_junction(who))),
+		transact_instruction(call),
+	])
+}
+
+fn contribute_call(project_key: ProjectIndex, value: parachain::Balance) -> crate::Call<parachain::Runtime> {
+	crate::Call::contribute { project_key, value }
+}
+
+fn vote_call(project_key: ProjectIndex, milestone_index: MilestoneIndex, approve_milestone: bool) -> crate::Call<parachain::Runtime> {
+	crate::Call::vote_on_milestone { project_key, milestone_index, approve_milestone }
+}
+
+fn account_junction(who: AccountId32) -> Junction {
+	Junction::AccountId32 { network: NetworkId::Polkadot, id: who.into() }
+}
+
+fn relay_account_location(who: AccountId32) -> MultiLocation {
+	MultiLocation::new(1, X1(account_junction(who)))
+}
+
+fn sibling_account_location(para_id: u32, who: AccountId32) -> MultiLocation {
+	MultiLocation::new(1, X2(Parachain(para_id), account_junction(who)))
+}
+
+fn send_from_relay(message: Xcm<()>) {
+	Relay::execute_with(|| assert_ok!(relay_chain::XcmRouter::send_xcm(Parachain(1), message)));
+}
+
+fn send_from_sibling(message: Xcm<()>) {
+	ParaB::execute_with(|| assert_ok!(parachain::XcmRouter::send_xcm(MultiLocation::new(1, X1(Parachain(1))), message)));
+}
+
+#[test]
+fn relay_account_contributes_via_transact() {
+	MockNet::reset();
+
+	let project_key = setup_round();
+	let (contributor, funds) = fund_derived_account(relay_account_location(BOB));
+
+	send_from_relay(transact(BOB, contribute_call(project_key, 500)));
+
+	ParaA::execute_with(|| {
+		assert_eq!(parachain::ImbueProposals::contributions(project_key, &contributor), 500);
+		assert_eq!(parachain::ImbueProposals::projects(project_key).raised_funds, 500);
+		assert_eq!(parachain::Balances::free_balance(&contributor), funds - 500);
+		assert_eq!(parachain::Balances::free_balance(&parent_account_id()), INITIAL_BALANCE - transact_fee());
+	});
+}
+
+#[test]
+fn sibling_account_contributes_and_votes_via_transact() {
+	MockNet::reset();
+
+	let project_key = setup_round();
+	let (contributor, funds) = fund_derived_account(sibling_account_location(2, BOB));
+
+	send_from_sibling(transact(BOB, contribute_call(project_key, 500)));
+
+	ParaA::execute_with(|| {
+		assert_eq!(parachain::ImbueProposals::contributions(project_key, &contributor), 500);
+		assert_eq!(parachain::Balances::free_balance(&contributor), funds - 500);
+		assert_ok!(parachain::ImbueProposals::submit_milestone(parachain::Origin::signed(ALICE), project_key, 0, b"evidence".to_vec()));
+	});
+
+	send_from_sibling(transact(BOB, vote_call(project_key, 0, true)));
+
+	ParaA::execute_with(|| {
+		let vote = parachain::ImbueProposals::user_votes((contributor.clone(), project_key, 0)).unwrap();
+		assert_eq!(vote.option, VoteOption::Yay);
+		assert_eq!(vote.weight, 500);
+		assert_eq!(parachain::ImbueProposals::milestone_votes((project_key, 0)).yay, 500);
+		assert_eq!(parachain::Balances::free_balance(&contributor), funds - 500);
+		assert_eq!(parachain::Balances::free_balance(&sibling_account_id(2)), INITIAL_BALANCE - 2 * transact_fee());
+	});
+}
+
+#[test]
+fn transact_without_buying_execution_is_rejected() {
+	MockNet::reset();
+
+	let project_key = setup_round();
+	let (contributor, funds) = fund_derived_account(relay_account_location(BOB));
+
+	send_from_relay(Xcm(vec![
+		DescendOrigin(X1(account_junction(BOB))),
+		transact_instruction(contribute_call(project_key, 500)),
+	]));
+
+	ParaA::execute_with(|| {
+		assert!(parachain::System::events().iter().any(|record| matches!(
+			record.event,
+			parachain::Event::MsgQueue(parachain::mock_msg_queue::Event::ExecutedDownward(_, Outcome::Error(XcmError::Barrier)))
+		)));
+		assert_eq!(parachain::ImbueProposals::contributions(project_key, &contributor), 0);
+		assert_eq!(parachain::Balances::free_balance(&contributor), funds);
+	});
+}
+
+#[test]
+fn remote_accounts_do_not_alias_local_ones() {
+	MockNet::reset();
+
+	let from_relay = derived_account(relay_account_location(BOB));
+	let from_sibling = derived_account(sibling_account_location(2, BOB));
+
+	assert_ne!(from_relay, BOB);
+	assert_ne!(from_sibling, BOB);
+	assert_ne!(from_relay, from_sibling);
+}
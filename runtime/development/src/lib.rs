@@ -34,7 +34,7 @@ use sp_runtime::{
 	ApplyExtrinsicResult,Perbill,Percent, Permill,
 };
 
-use sp_std::{marker::PhantomData, prelude::*, cmp::Ordering};
+use sp_std::{prelude::*, cmp::Ordering};
 
 #[cfg(feature = "std")]
 use sp_version::NativeVersion;
@@ -70,14 +70,14 @@ use parachains_common::{
 };
 
 use xcm_builder::{AsPrefixedGeneralIndex, ConvertedConcreteAssetId, FungiblesAdapter};
-use xcm_executor::{Config,traits::{JustTry, ShouldExecute}, XcmExecutor};
+use xcm_executor::{Config,traits::JustTry, XcmExecutor};
 
 // XCM imports
 use pallet_xcm::{EnsureXcm, IsMajorityOfBody, XcmPassthrough};
 use polkadot_parachain::primitives::Sibling;
 use xcm::latest::prelude::*;
 use xcm_builder::{
-	AccountId32Aliases, CurrencyAdapter,
+	Account32Hash, AccountId32Aliases, AllowTopLevelPaidExecutionFrom, CurrencyAdapter,
 	EnsureXcmOrigin, FixedWeightBounds, IsConcrete, LocationInverter, NativeAsset,
 	ParentAsSuperuser, ParentIsDefault, RelayChainAsNative, SiblingParachainAsNative,
 	SiblingParachainConvertsVia, SignedAccountId32AsNative, SignedToAccountId32,
@@ -280,6 +280,9 @@ pub type LocationToAccountId = (
 	SiblingParachainConvertsVia<Sibling, AccountId>,
 	// Straight up local `AccountId32` origins just alias directly to `AccountId`.
 	AccountId32Aliases<RococoNetwork, AccountId>,
+	// Any other location, such as an account on the relay chain or a sibling, gets an account
+	// derived from the hash of its location so it can contribute and vote through `Transact`.
+	Account32Hash<RococoNetwork, AccountId>,
 );
 
 /// Means for transacting assets on this chain.
@@ -366,23 +369,11 @@ match_type! {
 
 pub type Barrier = (
 	TakeWeightCredit,
-	AllowAnyPaidExecutionFrom<Everything>,
+	// Messages must pay for their execution before doing anything else
+	AllowTopLevelPaidExecutionFrom<Everything>,
 	// AllowUnpaidExecutionFrom<IsInVec<AllowUnpaidFrom>>,	// <- Parent gets free execution
 );
 
-
-pub struct AllowAnyPaidExecutionFrom<T>(PhantomData<T>);
-impl<T: Contains<MultiLocation>> ShouldExecute for AllowAnyPaidExecutionFrom<T> {
-	fn should_execute<Call>(
-		origin: &MultiLocation,
-		_message: &mut Xcm<Call>,
-		_max_weight: Weight,
-		_weight_credit: &mut Weight,
-	) -> Result<(), ()> {
-		ensure!(T::contains(origin), ());
-		Ok(())
-	}
-}
 parameter_types! {
 	pub StatemintLocation: MultiLocation = MultiLocation::new(1, X1(Parachain(1000)));
 }
@@ -703,10 +694,52 @@ impl pallet_identity::Config for Runtime {
 
 parameter_types! {
 	pub const ProposalsPalletId: PalletId = PalletId(*b"imbgrant");
-	pub const MaxProposalsPerRound: u32 = 256;
+	pub const MaxProposalsPerRound: u32 = 64;
+	pub const MaxMilestonesPerProject: u32 = 100;
+	pub const MaxUpcomingRounds: u32 = 16;
 	pub const MaxWithdrawalExpiration: BlockNumber = 180 * DAYS;
+	pub const MilestoneVotingPeriod: BlockNumber = 7 * DAYS;
+	pub const MilestoneApprovalThreshold: Perbill = Perbill::from_percent(50);
+	pub const MilestoneMinTurnout: Perbill = Perbill::from_percent(10);
+	pub MaxFinalizationWeight: Weight = Perbill::from_percent(10) * RuntimeBlockWeights::get().max_block;
+	pub const MaxStringLength: u32 = 256;
+	pub const MaxContentLength: u32 = 2048;
+	pub const ProjectDepositBase: Balance = currency::deposit(1, 0);
+	pub const ProjectDepositPerByte: Balance = currency::deposit(0, 1);
+	pub const DisputeBond: Balance = 100 * IMBU;
+	pub const DisputePeriod: BlockNumber = 7 * DAYS;
 }
 
+type ProposalsScheduleOrigin = EnsureOneOf<
+	AccountId,
+	EnsureRoot<AccountId>,
+	pallet_collective::EnsureProportionMoreThan<_1, _2, AccountId, CouncilInstance>,
+>;
+
+type ProposalsApproveOrigin = EnsureOneOf<
+	AccountId,
+	EnsureRoot<AccountId>,
+	pallet_collective::EnsureProportionAtLeast<_3, _5, AccountId, CouncilInstance>,
+>;
+
+type ProposalsCancelOrigin = EnsureOneOf<
+	AccountId,
+	EnsureRoot<AccountId>,
+	pallet_collective::EnsureProportionMoreThan<_1, _2, AccountId, CouncilInstance>,
+>;
+
+type ProposalsAdminOrigin = EnsureOneOf<
+	AccountId,
+	EnsureRoot<AccountId>,
+	pallet_collective::EnsureProportionAtLeast<_2, _3, AccountId, CouncilInstance>,
+>;
+
+type ProposalsDisputeOrigin = EnsureOneOf<
+	AccountId,
+	EnsureRoot<AccountId>,
+	pallet_collective::EnsureProportionMoreThan<_1, _2, AccountId, TechCommitteeInstance>,
+>;
+
 
 
 
@@ -754,9 +787,38 @@ impl proposals::Config for Runtime {
 	type Event = Event;
 	type PalletId = ProposalsPalletId;
 	type Currency = Balances;
+	type AssetId = AssetId;
+	type Assets = Assets;
 	type MaxProposalsPerRound = MaxProposalsPerRound;
+	type MaxMilestonesPerProject = MaxMilestonesPerProject;
+	type MaxUpcomingRounds = MaxUpcomingRounds;
 	type MaxWithdrawalExpiration = MaxWithdrawalExpiration;
-	type WeightInfo = ();
+	type MilestoneVotingPeriod = MilestoneVotingPeriod;
+	type MilestoneApprovalThreshold = MilestoneApprovalThreshold;
+	type MilestoneMinTurnout = MilestoneMinTurnout;
+	type MaxFinalizationWeight = MaxFinalizationWeight;
+	type Treasury = Treasury;
+	type MaxStringLength = MaxStringLength;
+	type MaxContentLength = MaxContentLength;
+	type ProjectDepositBase = ProjectDepositBase;
+	type ProjectDepositPerByte = ProjectDepositPerByte;
+	// More than half of the council is required (or root) to schedule or cancel rounds
+	type ScheduleOrigin = ProposalsScheduleOrigin;
+	// At least three-fifths majority of the council is required (or root) to release milestone funds
+	type ApproveOrigin = ProposalsApproveOrigin;
+	// More than half of the council is required (or root) to cancel proposals and spam projects
+	type CancelOrigin = ProposalsCancelOrigin;
+	// At least two-thirds of the council is required (or root) to change settings
+	type AdminOrigin = ProposalsAdminOrigin;
+	// More than half of the technical committee is required (or root) to settle milestone disputes
+	type DisputeOrigin = ProposalsDisputeOrigin;
+	type DisputeBond = DisputeBond;
+	type DisputePeriod = DisputePeriod;
+	type ScheduledCall = Call;
+	type PalletsOrigin = OriginCaller;
+	type Scheduler = Scheduler;
+	type VoteWeigher = proposals::StandardVoteWeigher<Runtime>;
+	type WeightInfo = proposals::weights::SubstrateWeight<Runtime>;
 }
 
 construct_runtime! {
@@ -794,7 +856,7 @@ construct_runtime! {
 		DmpQueue: cumulus_pallet_dmp_queue::{Pallet, Call, Storage, Event<T>} = 53,
 
 		// Imbue Pallets
-		ImbueProposals: proposals::{Pallet, Call, Storage, Event<T>},
+		ImbueProposals: proposals::{Pallet, Call, Storage, Event<T>, Config<T>},
 	}
 }
 
@@ -930,6 +992,98 @@ impl_runtime_apis! {
 			ParachainSystem::collect_collation_info()
 		}
 	}
+
+	impl proposals_rpc_runtime_api::ProposalsApi<Block, AccountId, Balance, BlockNumber> for Runtime {
+		fn get_projects() -> Vec<proposals::Project<AccountId, Balance, BlockNumber>> {
+			ImbueProposals::get_projects()
+		}
+
+		fn get_project(project_key: proposals::ProjectIndex) -> Option<proposals::Project<AccountId, Balance, BlockNumber>> {
+			ImbueProposals::try_get_project(project_key)
+		}
+
+		fn get_projects_by_state(state: proposals::ProjectState) -> Vec<proposals::ProjectIndex> {
+			ImbueProposals::get_projects_by_state(state)
+		}
+
+		fn get_active_round() -> Option<(proposals::RoundIndex, proposals::Round<Balance, BlockNumber>)> {
+			ImbueProposals::get_active_round()
+		}
+
+		fn get_round_projects(round_index: proposals::RoundIndex) -> Vec<proposals::ProjectIndex> {
+			ImbueProposals::get_round_projects(round_index)
+		}
+
+		fn get_milestone_votes(project_key: proposals::ProjectIndex) -> Vec<(proposals::MilestoneIndex, proposals::Vote<Balance>)> {
+			ImbueProposals::get_milestone_votes(project_key)
+		}
+
+		fn get_contributor_funds(project_key: proposals::ProjectIndex, who: AccountId) -> proposals::ContributorFunds<Balance> {
+			ImbueProposals::get_contributor_funds(project_key, who)
+		}
+
+		fn get_milestone_payouts(project_key: proposals::ProjectIndex) -> Vec<(proposals::MilestoneIndex, proposals::MilestonePayout<Balance, BlockNumber>)> {
+			ImbueProposals::get_milestone_payouts(project_key)
+		}
+
+		fn get_locked_funds(project_key: proposals::ProjectIndex) -> Balance {
+			ImbueProposals::get_locked_funds(project_key)
+		}
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	impl frame_benchmarking::Benchmark<Block> for Runtime {
+		fn benchmark_metadata(extra: bool) -> (
+			Vec<frame_benchmarking::BenchmarkList>,
+			Vec<frame_support::traits::StorageInfo>,
+		) {
+			use frame_benchmarking::{list_benchmark, Benchmarking, BenchmarkList};
+			use frame_support::traits::StorageInfoTrait;
+			use frame_system_benchmarking::Pallet as SystemBench;
+
+			let mut list = Vec::<BenchmarkList>::new();
+
+			list_benchmark!(list, extra, frame_system, SystemBench::<Runtime>);
+			list_benchmark!(list, extra, pallet_balances, Balances);
+			list_benchmark!(list, extra, proposals, ImbueProposals);
+
+			let storage_info = AllPalletsWithSystem::storage_info();
+
+			(list, storage_info)
+		}
+
+		fn dispatch_benchmark(
+			config: frame_benchmarking::BenchmarkConfig
+		) -> Result<Vec<frame_benchmarking::BenchmarkBatch>, sp_runtime::RuntimeString> {
+			use frame_benchmarking::{Benchmarking, BenchmarkBatch, add_benchmark, TrackedStorageKey};
+			use frame_system_benchmarking::Pallet as SystemBench;
+
+			impl frame_system_benchmarking::Config for Runtime {}
+
+			let whitelist: Vec<TrackedStorageKey> = vec![
+				// Block Number
+				hex_literal::hex!("26aa394eea5630e07c48ae0c9558cef702a5c1b19ab7a04f536c519aca4983ac").to_vec().into(),
+				// Total Issuance
+				hex_literal::hex!("c2261276cc9d1f8598ea4b6a74b15c2f57c875e4cff74148e4628f264b974c80").to_vec().into(),
+				// Execution Phase
+				hex_literal::hex!("26aa394eea5630e07c48ae0c9558cef7ff553b5a9862a516939d82b3d3d8661a").to_vec().into(),
+				// Event Count
+				hex_literal::hex!("26aa394eea5630e07c48ae0c9558cef70a98fdbe9ce6c55837576c60c7af3850").to_vec().into(),
+				// System Events
+				hex_literal::hex!("26aa394eea5630e07c48ae0c9558cef780d41e5e16056765bc8461851072c9d7").to_vec().into(),
+			];
+
+			let mut batches = Vec::<BenchmarkBatch>::new();
+			let params = (&config, &whitelist);
+
+			add_benchmark!(params, batches, frame_system, SystemBench::<Runtime>);
+			add_benchmark!(params, batches, pallet_balances, Balances);
+			add_benchmark!(params, batches, proposals, ImbueProposals);
+
+			if batches.is_empty() { return Err("Benchmark not found for this pallet.".into()) }
+			Ok(batches)
+		}
+	}
 }
 
 struct CheckInherents;